@@ -0,0 +1,123 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+// Per point
+const STRENGTH_ATTACK: u16 = 1;
+const VITALITY_MITIGATION: f64 = 0.01;
+const AGILITY_FLEE: f64 = 0.025;
+const AGILITY_DODGE: f64 = 0.015;
+const LUCK_FLATTENING: f64 = 0.05;
+const LUCK_CRIT: f64 = 0.01;
+
+const MAX_MITIGATION: f64 = 0.5;
+const BASE_FLEE_CHANCE: f64 = 0.75;
+const MAX_DODGE_CHANCE: f64 = 0.3;
+const BASE_CRIT_CHANCE: f64 = 0.05;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Attribute {
+    Strength,
+    Vitality,
+    Agility,
+    Luck,
+}
+
+impl Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strength => write!(f, "Strength"),
+            Self::Vitality => write!(f, "Vitality"),
+            Self::Agility => write!(f, "Agility"),
+            Self::Luck => write!(f, "Luck"),
+        }
+    }
+}
+
+impl Attribute {
+    pub fn get_registered() -> Vec<Self> {
+        vec![Self::Strength, Self::Vitality, Self::Agility, Self::Luck]
+    }
+}
+
+/// Points the player spends as they level, see `Player::attribute_points`
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Attributes {
+    pub strength: u16,
+    pub vitality: u16,
+    pub agility: u16,
+    pub luck: u16,
+}
+
+impl Attributes {
+    pub fn get(&self, attr: Attribute) -> u16 {
+        match attr {
+            Attribute::Strength => self.strength,
+            Attribute::Vitality => self.vitality,
+            Attribute::Agility => self.agility,
+            Attribute::Luck => self.luck,
+        }
+    }
+
+    pub fn raise(&mut self, attr: Attribute) {
+        let stat = match attr {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Vitality => &mut self.vitality,
+            Attribute::Agility => &mut self.agility,
+            Attribute::Luck => &mut self.luck,
+        };
+        *stat += 1;
+    }
+
+    /// Added to every hit
+    pub fn attack_bonus(&self) -> u16 {
+        self.strength * STRENGTH_ATTACK
+    }
+
+    /// Fraction of incoming damage shrugged off, on top of armor
+    pub fn mitigation(&self) -> f64 {
+        (self.vitality as f64 * VITALITY_MITIGATION).min(MAX_MITIGATION)
+    }
+
+    pub fn flee_chance(&self) -> f64 {
+        (BASE_FLEE_CHANCE + self.agility as f64 * AGILITY_FLEE).min(1.)
+    }
+
+    pub fn dodge_chance(&self) -> f64 {
+        (self.agility as f64 * AGILITY_DODGE).min(MAX_DODGE_CHANCE)
+    }
+
+    pub fn crit_chance(&self) -> f64 {
+        (BASE_CRIT_CHANCE + self.luck as f64 * LUCK_CRIT).min(1.)
+    }
+
+    /// Drop weights are raised to this power, anything under 1 evens out the odds,
+    /// so rarer drops come up more often
+    pub fn drop_luck(&self) -> f64 {
+        1. / (1. + self.luck as f64 * LUCK_FLATTENING)
+    }
+
+    /// What the current points in `attr` amount to
+    pub fn describe(&self, attr: Attribute) -> String {
+        match attr {
+            Attribute::Strength => format!("+{} attack", self.attack_bonus()),
+            Attribute::Vitality => format!("-{:.0}% damage taken", self.mitigation() * 100.),
+            Attribute::Agility => format!(
+                "{:.0}% chance to flee, {:.1}% to dodge",
+                self.flee_chance() * 100.,
+                self.dodge_chance() * 100.
+            ),
+            Attribute::Luck => format!(
+                "{:.0}% chance to crit, rare drops x{:.2} as likely",
+                self.crit_chance() * 100.,
+                self.rare_drop_boost()
+            ),
+        }
+    }
+
+    // How much more often a 1% drop comes up against a 50% one, compared to no luck
+    fn rare_drop_boost(&self) -> f64 {
+        let ratio = |luck: f64| (0.01_f64 / 0.5).powf(luck);
+        ratio(self.drop_luck()) / ratio(1.)
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::inform;
+use crate::show;
+use crate::warn;
+
+use super::classes::Class;
+use super::content::*;
+use super::entities::{AttackMove, Behavior, sprite_path};
+use super::items::{Element, ItemType};
+use super::status::{Infliction, StatusKind};
+use super::utilities::*;
+
+// How far encounter weights may stray from 1 before it's probably a typo
+const WEIGHT_TOLERANCE: f64 = 0.001;
+
+/// Loads the content pack in `dir` and reports anything that would only break at runtime.
+/// Returns whether the pack is clean
+pub fn check_content(dir: &Path) -> bool {
+    inform!("Checking content in `{}`...\n", dir.display());
+
+    let problems = match Content::load(dir) {
+        Ok(content) => find_problems(&content),
+        Err(er) => vec![er.to_string()],
+    };
+
+    for problem in &problems {
+        warn!("  ✗ {problem}");
+    }
+
+    if problems.is_empty() {
+        show!("{}", paint_text("No problems found", "Green"));
+    } else {
+        warn!("\nFound {} problem(s)", problems.len());
+    }
+    problems.is_empty()
+}
+
+pub fn find_problems(content: &Content) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    check_items(content, &mut problems);
+    check_drops("Base drops", &content.base_drops, &mut problems);
+
+    for place in &content.places {
+        check_entities(place, &mut problems);
+        if let Some(boss) = &place.boss {
+            check_boss(content, place, boss, &mut problems);
+        }
+        check_drops(&place.name, &place.drops, &mut problems);
+        check_sprite(
+            &format!("places/{}.ans", place.name.to_lowercase()),
+            &place.name,
+            &mut problems,
+        );
+    }
+    check_keys(content, &mut problems);
+    check_growth(content, &mut problems);
+    check_classes(content, &mut problems);
+
+    problems
+}
+
+fn check_items(content: &Content, problems: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    for itm in &content.items {
+        // `Player::has_item` goes by name, so these would be indistinguishable
+        if !seen.insert(itm.name.as_str()) {
+            problems.push(format!("Item `{}` is defined more than once", itm.name));
+        }
+        if let ItemType::Armor { reduction, resists } = itm.item_type {
+            if !(0. ..=1.).contains(&reduction) {
+                problems.push(format!(
+                    "Item `{}` has a damage reduction of {reduction}, expected 0-1",
+                    itm.name
+                ));
+            }
+            for element in Element::get_registered() {
+                let resist = resists.get(element);
+                if !(-1. ..=1.).contains(&resist) {
+                    problems.push(format!(
+                        "Item `{}` has a {element} resistance of {resist}, expected -1 to 1",
+                        itm.name
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn check_entities(place: &PlaceDef, problems: &mut Vec<String>) {
+    if place.entities.is_empty() {
+        return problems.push(format!("{}: No entities to encounter", place.name));
+    }
+
+    for ent in &place.entities {
+        let EntityDef {
+            name,
+            max_health,
+            damage: (min, max),
+            weight,
+            moves,
+            inflicts,
+            behaviors,
+            companions,
+            inescapable: _,
+            pursuit,
+            drops,
+            drop_chance,
+        } = ent;
+
+        if *max_health == 0 {
+            problems.push(format!("{}: `{name}` has no health", place.name));
+        }
+        if min > max {
+            problems.push(format!(
+                "{}: `{name}` has a damage range of ({min}, {max}), min is above max",
+                place.name
+            ));
+        }
+        if *weight <= 0. {
+            problems.push(format!(
+                "{}: `{name}` has an encounter weight of {weight}, it'll never appear",
+                place.name
+            ));
+        }
+        let owner = format!("{}: `{name}`", place.name);
+        if let Some(pursuit) = pursuit
+            && !(0. ..=1.).contains(pursuit)
+        {
+            problems.push(format!(
+                "{owner} has a pursuit of {pursuit}, expected between 0 and 1"
+            ));
+        }
+        if let Some(chance) = drop_chance
+            && !(0. ..=1.).contains(chance)
+        {
+            problems.push(format!(
+                "{owner} has a drop chance of {chance}, expected between 0 and 1"
+            ));
+        }
+        check_drops(&owner, drops, problems);
+        for attack_move in moves {
+            check_move(&owner, attack_move, problems);
+        }
+        if let Some(infliction) = inflicts {
+            check_infliction(&owner, infliction, problems);
+        }
+        for behavior in behaviors {
+            check_behavior(&owner, behavior, place, problems);
+        }
+        for CompanionDef {
+            name: companion,
+            count: (min, max),
+        } in companions
+        {
+            if !place.entities.iter().any(|ent| &ent.name == companion) {
+                problems.push(format!(
+                    "{owner} brings along `{companion}`, which isn't in {}",
+                    place.name
+                ));
+            }
+            if min > max {
+                problems.push(format!(
+                    "{owner} brings along ({min}, {max}) `{companion}`, min is above max"
+                ));
+            }
+        }
+        check_sprite(&sprite_path(name, &place.name), name, problems);
+    }
+
+    let total: f64 = place.entities.iter().map(|ent| ent.weight).sum();
+    if (total - 1.).abs() > WEIGHT_TOLERANCE {
+        problems.push(format!(
+            "{}: Encounter weights add up to {total:.3}, expected 1",
+            place.name
+        ));
+    }
+}
+
+fn check_boss(content: &Content, place: &PlaceDef, boss: &BossDef, problems: &mut Vec<String>) {
+    let BossDef {
+        name,
+        max_health,
+        damage: (min, max),
+        moves,
+        inflicts,
+        behaviors,
+        phases,
+        key,
+    } = boss;
+    let owner = format!("{}: Boss `{name}`", place.name);
+
+    if *max_health == 0 {
+        problems.push(format!("{owner} has no health"));
+    }
+    if min > max {
+        problems.push(format!(
+            "{owner} has a damage range of ({min}, {max}), min is above max"
+        ));
+    }
+    if let Some(key) = key
+        && content
+            .item(key)
+            .is_none_or(|itm| itm.item_type != ItemType::Key)
+    {
+        problems.push(format!(
+            "{owner} hands over `{key}`, which isn't a defined key"
+        ));
+    }
+    check_sprite(&sprite_path(name, &place.name), name, problems);
+
+    let mut stages = vec![(moves, inflicts, behaviors)];
+    let mut last_below = 1.;
+    for phase in phases {
+        if !(phase.below > 0. && phase.below < last_below) {
+            problems.push(format!(
+                "{owner} has a phase starting below {} of its HP, expected above 0 and below the phase before",
+                phase.below
+            ));
+        }
+        if let Some((min, max)) = phase.damage
+            && min > max
+        {
+            problems.push(format!(
+                "{owner} has a phase with a damage range of ({min}, {max}), min is above max"
+            ));
+        }
+        last_below = phase.below;
+        stages.push((&phase.moves, &phase.inflicts, &phase.behaviors));
+    }
+
+    for (moves, inflicts, behaviors) in stages {
+        for attack_move in moves {
+            check_move(&owner, attack_move, problems);
+        }
+        if let Some(infliction) = inflicts {
+            check_infliction(&owner, infliction, problems);
+        }
+        for behavior in behaviors {
+            // A boss that ran off would still count as beaten
+            if let Behavior::Flee { .. } = behavior {
+                problems.push(format!(
+                    "{owner} has a Flee behavior, bosses have to be beaten"
+                ));
+            }
+            check_behavior(&owner, behavior, place, problems);
+        }
+    }
+}
+
+fn check_move(owner: &str, attack_move: &AttackMove, problems: &mut Vec<String>) {
+    let AttackMove {
+        name,
+        weight,
+        damage,
+        ..
+    } = attack_move;
+
+    if *weight <= 0. {
+        problems.push(format!(
+            "{owner}'s move `{name}` has a weight of {weight}, it'll never be used"
+        ));
+    }
+    if let Some((min, max)) = damage
+        && min > max
+    {
+        problems.push(format!(
+            "{owner}'s move `{name}` has a damage range of ({min}, {max}), min is above max"
+        ));
+    }
+}
+
+fn check_behavior(owner: &str, behavior: &Behavior, place: &PlaceDef, problems: &mut Vec<String>) {
+    // Shares and chances must be above 0 and at most 1, multipliers above 0
+    let (kind, shares, multiplier) = match behavior {
+        Behavior::Flee { below, chance } => ("Flee", vec![below, chance], None),
+        Behavior::Heal { below, share, uses } => {
+            if *uses == 0 {
+                problems.push(format!("{owner} has a Heal behavior with 0 uses"));
+            }
+            ("Heal", vec![below, share], None)
+        }
+        Behavior::Enrage { below, multiplier } => ("Enrage", vec![below], Some(multiplier)),
+        Behavior::Charge { chance, multiplier } => ("Charge", vec![chance], Some(multiplier)),
+        Behavior::CallForHelp {
+            ally,
+            below,
+            chance,
+        } => {
+            if !place.entities.iter().any(|ent| &ent.name == ally) {
+                problems.push(format!(
+                    "{owner} calls for help from `{ally}`, which isn't in {}",
+                    place.name
+                ));
+            }
+            ("CallForHelp", vec![below, chance], None)
+        }
+    };
+
+    for value in shares {
+        if !(*value > 0. && *value <= 1.) {
+            problems.push(format!(
+                "{owner}'s {kind} behavior has a share or chance of {value}, expected above 0 and at most 1"
+            ));
+        }
+    }
+    if let Some(multiplier) = multiplier
+        && *multiplier <= 0.
+    {
+        problems.push(format!(
+            "{owner}'s {kind} behavior has a multiplier of {multiplier}, expected above 0"
+        ));
+    }
+}
+
+fn check_infliction(owner: &str, infliction: &Infliction, problems: &mut Vec<String>) {
+    let Infliction {
+        status,
+        chance,
+        turns,
+        potency,
+    } = *infliction;
+
+    if !(chance > 0. && chance <= 1.) {
+        problems.push(format!(
+            "{owner} inflicts {status} with a chance of {chance}, expected above 0 and at most 1"
+        ));
+    }
+    if turns == 0 {
+        problems.push(format!("{owner} inflicts {status} for 0 turns"));
+    }
+    let needs_potency = matches!(
+        status,
+        StatusKind::Poison | StatusKind::Bleed | StatusKind::Burn | StatusKind::Regen
+    );
+    if needs_potency && potency == 0 {
+        problems.push(format!(
+            "{owner} inflicts {status} with no potency, it won't do anything"
+        ));
+    }
+}
+
+// Chances are weights against whatever they're rolled with (see `DropDef`), so there's
+// no total to check, only that each one can come up at all
+fn check_drops(table: &str, drops: &[DropDef], problems: &mut Vec<String>) {
+    for drop in drops {
+        if !(drop.chance > 0. && drop.chance <= 1.) {
+            problems.push(format!(
+                "{table}: `{}` has a drop chance of {}, expected above 0 and at most 1",
+                drop.item, drop.chance
+            ));
+        }
+    }
+}
+
+fn check_keys(content: &Content, problems: &mut Vec<String>) {
+    // Bosses hand over their key, so it counts as a drop
+    let boss_keys = content
+        .places
+        .iter()
+        .filter_map(|place| place.boss.as_ref()?.key.as_deref());
+    let dropped: HashSet<&str> = content
+        .places
+        .iter()
+        .flat_map(|place| {
+            let entity_drops = place.entities.iter().flat_map(|ent| &ent.drops);
+            place.drops.iter().chain(entity_drops)
+        })
+        .chain(&content.base_drops)
+        .map(|drop| drop.item.as_str())
+        .chain(boss_keys)
+        .collect();
+
+    for itm in &content.items {
+        if itm.item_type == ItemType::Key
+            && itm.name.ends_with(" Key")
+            && !dropped.contains(itm.name.as_str())
+        {
+            problems.push(format!("Key `{}` never drops anywhere", itm.name));
+        }
+    }
+
+    for place in &content.places {
+        let Some(key) = &place.key else {
+            continue;
+        };
+        match content.item(key) {
+            None => problems.push(format!(
+                "{}: Requires `{key}`, which isn't a defined item",
+                place.name
+            )),
+            Some(itm) if itm.item_type != ItemType::Key => problems.push(format!(
+                "{}: Requires `{key}`, which is a(n) {} item rather than a key",
+                place.name, itm.item_type
+            )),
+            Some(_) if !dropped.contains(key.as_str()) => problems.push(format!(
+                "{}: Requires `{key}`, which never drops, so it can't be reached",
+                place.name
+            )),
+            _ => {}
+        }
+    }
+}
+
+fn check_growth(content: &Content, problems: &mut Vec<String>) {
+    for tier in &content.growth {
+        // Players start at level 1, so it's never reached
+        if tier.from_level < 2 {
+            problems.push(format!(
+                "Growth: A tier starts at level {}, levels are only gained from 2",
+                tier.from_level
+            ));
+        }
+    }
+    for pair in content.growth.windows(2) {
+        if pair[0].from_level == pair[1].from_level {
+            problems.push(format!(
+                "Growth: More than one tier starts at level {}",
+                pair[0].from_level
+            ));
+        }
+    }
+}
+
+fn check_classes(content: &Content, problems: &mut Vec<String>) {
+    for class in Class::get_registered() {
+        let weapon = class.starting_weapon();
+        match content.item(weapon) {
+            None => problems.push(format!(
+                "{class}: Starts with `{weapon}`, which isn't a defined item"
+            )),
+            Some(itm) if !matches!(itm.item_type, ItemType::Weapon { .. }) => {
+                problems.push(format!(
+                    "{class}: Starts with `{weapon}`, which is a(n) {} item rather than a weapon",
+                    itm.item_type
+                ))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_sprite(path: &str, owner: &str, problems: &mut Vec<String>) {
+    if !Path::new(SPRITE_DIR).join(path).is_file() {
+        problems.push(format!(
+            "`{owner}` is missing its sprite at `{SPRITE_DIR}/{path}`"
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::attributes::Attributes;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Class {
+    Warrior,
+    Ranger,
+    Mystic,
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warrior => write!(f, "Warrior"),
+            Self::Ranger => write!(f, "Ranger"),
+            Self::Mystic => write!(f, "Mystic"),
+        }
+    }
+}
+
+impl Class {
+    pub fn get_registered() -> Vec<Self> {
+        vec![Self::Warrior, Self::Ranger, Self::Mystic]
+    }
+
+    pub fn desc(&self) -> &'static str {
+        match self {
+            Self::Warrior => "Sturdy and strong, hits hardest when it counts",
+            Self::Ranger => "Quick on their feet, keeps enemies at bow's length",
+            Self::Mystic => "Frail but patient, mends their own wounds",
+        }
+    }
+
+    /// Looked up in the content directory, falls back to `Fists` when it's missing
+    pub fn starting_weapon(&self) -> &'static str {
+        match self {
+            Self::Warrior => "Rusty Blade",
+            Self::Ranger => "Shortbow",
+            Self::Mystic => "Gnarled Staff",
+        }
+    }
+
+    pub fn max_health(&self) -> u16 {
+        match self {
+            Self::Warrior => 120,
+            Self::Ranger => 100,
+            Self::Mystic => 90,
+        }
+    }
+
+    pub fn attributes(&self) -> Attributes {
+        match self {
+            Self::Warrior => Attributes {
+                strength: 2,
+                vitality: 2,
+                ..Default::default()
+            },
+            Self::Ranger => Attributes {
+                agility: 3,
+                luck: 1,
+                ..Default::default()
+            },
+            Self::Mystic => Attributes {
+                vitality: 1,
+                luck: 3,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Usable once per fight, see `entities::use_ability`
+    pub fn ability(&self) -> Ability {
+        match self {
+            Self::Warrior => Ability::Cleave,
+            Self::Ranger => Ability::PinningShot,
+            Self::Mystic => Ability::Mend,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Ability {
+    Cleave,      // Two swings in one turn
+    PinningShot, // Regular hit, the entity can't hit back this round
+    Mend,        // Heals a share of max HP
+}
+
+impl Display for Ability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cleave => write!(f, "Cleave"),
+            Self::PinningShot => write!(f, "Pinning Shot"),
+            Self::Mend => write!(f, "Mend"),
+        }
+    }
+}
+
+impl Ability {
+    pub fn desc(&self) -> &'static str {
+        match self {
+            Self::Cleave => "Two swings in one turn, each can miss or crit on its own",
+            Self::PinningShot => "A hit that leaves the enemy unable to strike back",
+            Self::Mend => "Heal 25% of your max HP, then regenerate for 3 turns",
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use super::entities::{AttackMove, Behavior};
+use super::items::*;
+use super::places::Location;
+use super::status::Infliction;
+
+/// Where designers drop new places and items, no recompiling needed
+pub const CONTENT_DIR: &str = "src/content";
+
+static CONTENT: OnceLock<Content> = OnceLock::new();
+
+pub struct Content {
+    pub items: Vec<Item>,
+    pub base_drops: Vec<DropDef>,
+    pub places: Vec<PlaceDef>,   // Sorted by `order`, i.e., travel order
+    pub growth: Vec<GrowthTier>, // Sorted by `from_level`
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct DropDef {
+    pub item: String,
+    pub chance: f64, // A weight against the drops it's rolled with, not a percentage
+    #[serde(default)]
+    pub duplicates: bool,
+}
+
+/// Fought alongside an entity, looked up by `name` in the same place
+#[derive(Deserialize, Clone)]
+pub struct CompanionDef {
+    pub name: String,
+    pub count: (u16, u16), // Min, Max
+}
+
+#[derive(Deserialize, Clone)]
+pub struct EntityDef {
+    pub name: String,
+    pub max_health: u16,
+    pub damage: (u16, u16),
+    pub weight: f64, // Encounter % (e.g., 0.01 = 1%)
+    #[serde(default)]
+    pub moves: Vec<AttackMove>,
+    #[serde(default)]
+    pub inflicts: Option<Infliction>,
+    #[serde(default)]
+    pub behaviors: Vec<Behavior>,
+    #[serde(default)]
+    pub companions: Vec<CompanionDef>,
+    #[serde(default)]
+    pub inescapable: bool,
+    pub pursuit: Option<f64>, // Taken off the player's flee chance, 0.1 when unset
+    #[serde(default)]
+    pub drops: Vec<DropDef>, // Merged with the place's
+    pub drop_chance: Option<f64>, // Of dropping anything once defeated, 0.1 when unset
+}
+
+/// A place's boss, see `Player::bosses_defeated`. Can't be fled from
+#[derive(Deserialize, Clone)]
+pub struct BossDef {
+    pub name: String,
+    pub max_health: u16,
+    pub damage: (u16, u16),
+    #[serde(default)]
+    pub moves: Vec<AttackMove>,
+    #[serde(default)]
+    pub inflicts: Option<Infliction>,
+    #[serde(default)]
+    pub behaviors: Vec<Behavior>,
+    #[serde(default)]
+    pub phases: Vec<PhaseDef>, // In the order they take over
+    pub key: Option<String>, // Handed over when it's defeated
+}
+
+/// Takes over a boss's attacks once it drops `below` a share of its max HP
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct PhaseDef {
+    pub below: f64,
+    pub announce: String,
+    pub damage: Option<(u16, u16)>, // Unchanged when unset
+    #[serde(default)]
+    pub moves: Vec<AttackMove>,
+    #[serde(default)]
+    pub inflicts: Option<Infliction>,
+    #[serde(default)]
+    pub behaviors: Vec<Behavior>,
+}
+
+#[derive(Deserialize)]
+pub struct PlaceDef {
+    pub name: String,
+    pub desc: String,
+    #[serde(default)]
+    pub order: i32,
+    pub key: Option<String>, // Item needed to travel here
+    #[serde(default)]
+    pub entities: Vec<EntityDef>,
+    #[serde(default)]
+    pub drops: Vec<DropDef>,
+    pub boss: Option<BossDef>,
+}
+
+/// Stats gained on reaching a level
+#[derive(Deserialize, Clone, Copy, Default)]
+pub struct Growth {
+    #[serde(default)]
+    pub max_health: u16,
+    #[serde(default)]
+    pub attack: u16,
+    #[serde(default)]
+    pub attribute_points: u16,
+}
+
+#[derive(Deserialize)]
+pub struct GrowthTier {
+    pub from_level: u16,
+    #[serde(flatten)]
+    pub growth: Growth,
+}
+
+#[derive(Deserialize, Default)]
+struct GrowthFile {
+    #[serde(default)]
+    tiers: Vec<GrowthTier>,
+}
+
+#[derive(Deserialize)]
+struct ItemsFile {
+    #[serde(default)]
+    items: Vec<Item>,
+    #[serde(default)]
+    base_drops: Vec<DropDef>,
+}
+
+pub enum ContentError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    NoPlaces,
+    DuplicatePlace(String),
+    UnknownItem { table: String, item: String },
+}
+
+impl Display for ContentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, er) => write!(f, "Couldn't read `{}`: `{er}`", path.display()),
+            Self::Parse(path, er) => write!(f, "Couldn't parse `{}`: {er}", path.display()),
+            Self::NoPlaces => write!(f, "No places are defined"),
+            Self::DuplicatePlace(name) => write!(f, "The place `{name}` is defined twice"),
+            Self::UnknownItem { table, item } => {
+                write!(
+                    f,
+                    "`{table}` refers to `{item}`, which isn't a defined item"
+                )
+            }
+        }
+    }
+}
+
+fn read_toml<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, ContentError> {
+    let content = fs::read_to_string(path).map_err(|er| ContentError::Io(path.into(), er))?;
+    toml::from_str(&content).map_err(|er| ContentError::Parse(path.into(), er))
+}
+
+impl Content {
+    /// Reads `items.toml` and every `places/*.toml` under `dir`
+    pub fn load(dir: &Path) -> Result<Self, ContentError> {
+        let ItemsFile { items, base_drops } = read_toml(&dir.join("items.toml"))?;
+
+        let places_dir = dir.join("places");
+        let entries =
+            fs::read_dir(&places_dir).map_err(|er| ContentError::Io(places_dir.clone(), er))?;
+
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        let mut places = paths
+            .iter()
+            .map(|path| read_toml::<PlaceDef>(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        places.sort_by_key(|place| place.order);
+
+        // Optional, without it levels are just a number
+        let growth_path = dir.join("growth.toml");
+        let GrowthFile { tiers: mut growth } = if growth_path.is_file() {
+            read_toml(&growth_path)?
+        } else {
+            GrowthFile::default()
+        };
+        growth.sort_by_key(|tier| tier.from_level);
+
+        let content = Self {
+            items,
+            base_drops,
+            places,
+            growth,
+        };
+        content.resolve()?;
+        Ok(content)
+    }
+
+    // Catches what would otherwise break mid-game. Out of range chances and min-above-max
+    // ranges are left to `check-content`, the rolls in `math` put up with them
+    fn resolve(&self) -> Result<(), ContentError> {
+        if self.places.is_empty() {
+            return Err(ContentError::NoPlaces);
+        }
+
+        for (i, place) in self.places.iter().enumerate() {
+            if self.places[..i].iter().any(|p| p.name == place.name) {
+                return Err(ContentError::DuplicatePlace(place.name.clone()));
+            }
+        }
+
+        let entity_tables = self.places.iter().flat_map(|p| {
+            let tables = p.entities.iter();
+            tables.map(move |ent| (format!("{}: {}", p.name, ent.name), &ent.drops))
+        });
+        let tables = std::iter::once((String::from("Base drops"), &self.base_drops))
+            .chain(self.places.iter().map(|p| (p.name.clone(), &p.drops)))
+            .chain(entity_tables);
+        for (table, drops) in tables {
+            if let Some(drop) = drops.iter().find(|d| self.item(&d.item).is_none()) {
+                return Err(ContentError::UnknownItem {
+                    table,
+                    item: drop.item.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn item(&self, name: &str) -> Option<&Item> {
+        self.items.iter().find(|itm| itm.name == name)
+    }
+
+    pub fn place(&self, location: &Location) -> Option<&PlaceDef> {
+        self.places.iter().find(|p| p.name == location.to_string())
+    }
+
+    pub fn growth_at(&self, level: u16) -> Growth {
+        self.growth
+            .iter()
+            .rfind(|tier| tier.from_level <= level)
+            .map(|tier| tier.growth)
+            .unwrap_or_default()
+    }
+
+    /// Turns a drop table into rollable `Drops`
+    pub fn drops(&self, table: &[DropDef]) -> Drops {
+        table
+            .iter()
+            .filter_map(|d| Some((self.item(&d.item)?.clone(), d.chance, d.duplicates)))
+            .collect()
+    }
+}
+
+/// Loads the content directory once, before anything asks for it
+pub fn init() -> Result<(), ContentError> {
+    if CONTENT.get().is_none() {
+        let content = Content::load(Path::new(CONTENT_DIR))?;
+        let _ = CONTENT.set(content);
+    }
+    Ok(())
+}
+
+pub fn get() -> &'static Content {
+    CONTENT
+        .get()
+        .expect("Content wasn't loaded, call `content::init` first")
+}
@@ -1,126 +1,1062 @@
-use input_macro::input;
+use std::fmt::Display;
+
+use serde::Deserialize;
 
 use crate::prompt;
+use crate::show;
 use crate::warn;
 
 // CMD K + W
-use super::game::view_inventory;
+use super::classes::Ability;
+use super::content::{self, BossDef, DropDef, EntityDef, PhaseDef};
+use super::frontend::{self, Panel};
+use super::items::{Drops, Element, ItemType};
+use super::places::Place;
 use super::player::*;
-use super::utilities::math::rng_from_range;
+use super::skills::Skill;
+use super::status::{Infliction, Status, StatusKind, Statuses};
+use super::utilities::math::{GameRng, HitOutcome, rng_from_range};
 use super::utilities::*;
 
-#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+// Of max HP, healed by `Ability::Mend` up front and then every turn it lasts
+const MEND_SHARE: f64 = 0.25;
+const MEND_REGEN_SHARE: f64 = 0.05;
+const MEND_REGEN_TURNS: u16 = 3;
+const HEAVY_STRIKE_MULTIPLIER: f64 = 1.75;
+const DRAIN_SHARE: f64 = 0.5; // Of the damage dealt
+const STUN_TURNS: u16 = 2;
+const HIT_CHANCE: f64 = 0.9;
+const DODGE_CHANCE: f64 = 0.05;
+const CRIT_CHANCE: f64 = 0.05;
+const BASE_DROP_CHANCE: f64 = 0.1; // Per enemy defeated, unless it has its own
+const BASE_PURSUIT: f64 = 0.1; // Taken off the player's flee chance, per enemy
+const LEVEL_FLEE: f64 = 0.01; // Added to the flee chance per player level
+const MIN_FLEE_CHANCE: f64 = 0.1;
+const FLEE_DROP_CHANCE: f64 = 0.25; // Of losing an item on the way out
+const LEVEL_SCALING: f64 = 0.03; // Added to entity health and damage per player level
+const ELITE_CHANCE: f64 = 0.08;
+const CHAMPION_CHANCE: f64 = 0.02;
+
+/// What happened since the player last chose what to do, shown under the fight
+pub type Log = Vec<String>;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum MoveKind {
+    #[default]
+    Normal,
+    Piercing,           // Ignores armor
+    Elemental(Element), // Ignores armor's reduction, but not its resistances
+}
+
+/// One of an entity's attacks, picked by weight every time it attacks
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct AttackMove {
+    pub name: String,
+    #[serde(default)]
+    pub kind: MoveKind,
+    pub weight: f64,
+    pub damage: Option<(u16, u16)>, // The entity's own when unset
+}
+
+/// Something an entity may do besides attacking, declared in the content directory.
+/// Checked in order every turn, the first one that kicks in takes the turn (except `Enrage`).
+/// `below` is a share of max HP, e.g., 0.3 = under 30%
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum Behavior {
+    // Ends the fight, no XP
+    Flee {
+        below: f64,
+        chance: f64,
+    },
+    // Heals `share` of max HP
+    Heal {
+        below: f64,
+        share: f64,
+        uses: u16,
+    },
+    // Hits harder from then on, doesn't take the turn
+    Enrage {
+        below: f64,
+        multiplier: f64,
+    },
+    // Winds up, then hits next turn
+    Charge {
+        chance: f64,
+        multiplier: f64,
+    },
+    // Once, the ally joins the fight at the end of the round
+    CallForHelp {
+        ally: String,
+        below: f64,
+        chance: f64,
+    },
+}
+
+/// Where an entity's behaviors stand, reset every fight
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Conduct {
+    pub fled: bool,
+    pub enraged: Option<f64>,  // Damage multiplier
+    pub charging: Option<f64>, // Damage multiplier of the upcoming hit
+    pub heals_used: u16,
+    pub called_for_help: bool,
+    pub ally: Option<String>, // On its way, joins at the end of the round
+    pub pinned: bool,         // Skips its next turn, see `Ability::PinningShot`
+}
+
+/// How much tougher than usual an entity turned up, see `Entity::promoted`
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Rank {
+    #[default]
+    Normal,
+    Elite,
+    Champion,
+}
+
+impl Rank {
+    fn roll(rng: &mut GameRng) -> Self {
+        if math::bool_from_chance(rng, CHAMPION_CHANCE) {
+            Self::Champion
+        } else if math::bool_from_chance(rng, ELITE_CHANCE) {
+            Self::Elite
+        } else {
+            Self::Normal
+        }
+    }
+
+    // Put in front of the entity's name
+    fn prefixes(&self) -> &'static [&'static str] {
+        match self {
+            Self::Normal => &[],
+            Self::Elite => &["Frenzied", "Hardened", "Vicious"],
+            Self::Champion => &["Ancient", "Dread", "Towering"],
+        }
+    }
+
+    fn health_multiplier(&self) -> f64 {
+        match self {
+            Self::Normal => 1.,
+            Self::Elite => 1.5,
+            Self::Champion => 2.25,
+        }
+    }
+
+    fn damage_multiplier(&self) -> f64 {
+        match self {
+            Self::Normal => 1.,
+            Self::Elite => 1.25,
+            Self::Champion => 1.5,
+        }
+    }
+
+    /// On top of what its tougher stats already earn
+    pub fn xp_multiplier(&self) -> f64 {
+        match self {
+            Self::Normal => 1.,
+            Self::Elite => 1.5,
+            Self::Champion => 2.,
+        }
+    }
+
+    /// Its name's color in the fight
+    fn color(&self) -> Option<&'static str> {
+        match self {
+            Self::Normal => None,
+            Self::Elite => Some("Magenta"),
+            Self::Champion => Some("Yellow"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub struct Entity {
     pub name: String,
+    pub base_name: String, // Without a rank's prefix, e.g., for its sprite
+    pub rank: Rank,
     pub max_health: u16,
     pub cur_health: u16,
     pub damage: (u16, u16),
+    pub moves: Vec<AttackMove>, // A plain attack when empty
+    pub inflicts: Option<Infliction>,
+    pub behaviors: Vec<Behavior>,
+    pub phases: Vec<PhaseDef>, // Still to come
+    pub fleeable: bool,
+    pub pursuit: f64,        // Taken off the player's flee chance
+    pub drops: Vec<DropDef>, // On top of the place's
+    pub drop_chance: f64,
+    pub statuses: Statuses,
+    pub conduct: Conduct,
 }
 
 impl Entity {
-    pub fn new(name: &'static str, max_health: u16, damage: (u16, u16)) -> Self {
+    pub fn new(name: &str, max_health: u16, damage: (u16, u16)) -> Self {
         Self {
             name: name.to_owned(),
+            base_name: name.to_owned(),
+            rank: Rank::Normal,
             max_health,
             cur_health: max_health,
             damage,
+            moves: Vec::new(),
+            inflicts: None,
+            behaviors: Vec::new(),
+            phases: Vec::new(),
+            fleeable: true,
+            pursuit: BASE_PURSUIT,
+            drops: Vec::new(),
+            drop_chance: BASE_DROP_CHANCE,
+            statuses: Statuses::default(),
+            conduct: Conduct::default(),
+        }
+    }
+
+    pub fn from_def(def: &EntityDef) -> Self {
+        Self {
+            moves: def.moves.clone(),
+            inflicts: def.inflicts,
+            behaviors: def.behaviors.clone(),
+            fleeable: !def.inescapable,
+            pursuit: def.pursuit.unwrap_or(BASE_PURSUIT),
+            drops: def.drops.clone(),
+            drop_chance: def.drop_chance.unwrap_or(BASE_DROP_CHANCE),
+            ..Self::new(&def.name, def.max_health, def.damage)
         }
     }
 
-    pub fn encounter(&mut self, plr: &mut Player) {
-        encounter(self, plr);
+    pub fn from_boss(def: &BossDef) -> Self {
+        Self {
+            moves: def.moves.clone(),
+            inflicts: def.inflicts,
+            behaviors: def.behaviors.clone(),
+            phases: def.phases.clone(),
+            fleeable: false,
+            ..Self::new(&def.name, def.max_health, def.damage)
+        }
     }
 
-    fn get_damage(&self) -> u16 {
-        rng_from_range(self.damage)
+    /// Grows its health and damage with the player's level, so earlier places keep up
+    pub fn scaled(self, level: u16) -> Self {
+        let factor = 1. + level.saturating_sub(1) as f64 * LEVEL_SCALING;
+        self.strengthened(factor, factor)
     }
 
-    fn attack(&self, plr: &mut Player) {
-        plr.take_damage(self.get_damage(), false);
+    /// Every so often makes it an elite or a champion, with a prefix to show for it
+    pub fn promoted(mut self, rng: &mut GameRng) -> Self {
+        let rank = Rank::roll(rng);
+        let prefixes = rank.prefixes();
+        if prefixes.is_empty() {
+            return self;
+        }
+
+        let prefix = prefixes[rng_from_range(rng, (0, prefixes.len() - 1))];
+        self.name = format!("{prefix} {}", self.base_name);
+        self.rank = rank;
+        self.strengthened(rank.health_multiplier(), rank.damage_multiplier())
     }
 
-    fn is_alive(&self) -> bool {
+    fn strengthened(mut self, health: f64, damage: f64) -> Self {
+        let grow = |stat: u16, by: f64| (stat as f64 * by).round() as u16;
+        let grow_range = |(min, max): (u16, u16)| (grow(min, damage), grow(max, damage));
+
+        self.max_health = grow(self.max_health, health);
+        self.cur_health = self.max_health;
+        self.damage = grow_range(self.damage);
+        for attack_move in &mut self.moves {
+            attack_move.damage = attack_move.damage.map(grow_range);
+        }
+        self
+    }
+
+    /// Its own loot merged with the place's, see `Place::get_drops`
+    fn get_drops(&self, place: &Place) -> Drops {
+        let mut list = place.get_drops();
+        list.extend(content::get().drops(&self.drops));
+        list
+    }
+
+    fn get_damage(&self, rng: &mut GameRng, with: Option<&AttackMove>) -> u16 {
+        let range = with.and_then(|m| m.damage).unwrap_or(self.damage);
+        rng_from_range(rng, range)
+    }
+
+    fn choose_move(&self, rng: &mut GameRng) -> Option<&AttackMove> {
+        let weighted = self.moves.iter().map(|m| (m, m.weight)).collect();
+        math::weigh_vec(rng, weighted)
+    }
+
+    pub fn dodge_chance(&self) -> f64 {
+        DODGE_CHANCE
+    }
+
+    fn attack(&self, plr: &mut Player, multiplier: f64, log: &mut Log) {
+        let chosen = self.choose_move(&mut plr.rng);
+        let attack_name = chosen.map_or("attack", |m| m.name.as_str());
+        let kind = chosen.map_or(MoveKind::Normal, |m| m.kind);
+
+        let dodge_chance = plr.attributes.dodge_chance();
+        let outcome = math::roll_hit(&mut plr.rng, HIT_CHANCE, dodge_chance, CRIT_CHANCE);
+        let Some(verb) = hit_verb(outcome) else {
+            return log.push(match outcome {
+                HitOutcome::Dodge => {
+                    format!("[{outcome}] You dodged the {}'s {attack_name}", self.name)
+                }
+                _ => format!("[{outcome}] The {}'s {attack_name} missed you", self.name),
+            });
+        };
+
+        let rolled = self.get_damage(&mut plr.rng, chosen) as f64;
+        let multiplier =
+            multiplier * outcome.multiplier() * self.statuses.damage_dealt_multiplier();
+        let damage = (rolled * multiplier).round() as u16;
+        let (taken, kind_note) = match kind {
+            MoveKind::Normal => (plr.take_damage(damage, false), String::new()),
+            MoveKind::Piercing => (
+                plr.take_damage(damage, true),
+                String::from(", piercing your armor"),
+            ),
+            MoveKind::Elemental(element) => (
+                plr.take_elemental_damage(damage, element),
+                format!(" ({element})"),
+            ),
+        };
+        let msg = match chosen {
+            Some(m) => format!(
+                "[{outcome}] The {}'s {} {verb} you for {taken} damage{kind_note}",
+                self.name, m.name
+            ),
+            None => format!(
+                "[{outcome}] The {} {verb} you for {taken} damage",
+                self.name
+            ),
+        };
+        log.push(if outcome == HitOutcome::Crit {
+            warning(msg)
+        } else {
+            msg
+        });
+
+        if let Some(infliction) = self.inflicts
+            && plr.is_alive()
+            && math::bool_from_chance(&mut plr.rng, infliction.chance)
+        {
+            plr.statuses.apply(infliction.status());
+            log.push(warning(format!("You're {}!", infliction.status)));
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
         self.cur_health != 0
     }
+
+    /// Still alive and hasn't run off
+    pub fn in_fight(&self) -> bool {
+        self.is_alive() && !self.conduct.fled
+    }
+
+    /// Moves on to whichever phases its health has dropped into
+    fn advance_phase(&mut self, log: &mut Log) {
+        let health = self.cur_health as f64 / self.max_health as f64;
+        while self
+            .phases
+            .first()
+            .is_some_and(|phase| health < phase.below)
+        {
+            let phase = self.phases.remove(0);
+            log.push(warning(phase.announce));
+
+            self.damage = phase.damage.unwrap_or(self.damage);
+            self.moves = phase.moves;
+            self.inflicts = phase.inflicts;
+            self.behaviors = phase.behaviors;
+            // Every phase gets its own heals and call for help
+            self.conduct.heals_used = 0;
+            self.conduct.called_for_help = false;
+        }
+    }
+
+    /// Goes through the entity's behaviors as its turn starts. Returns the multiplier
+    /// of its attack, or `None` if it did something else with the turn
+    fn behave(&mut self, rng: &mut GameRng, log: &mut Log) -> Option<f64> {
+        let rage = self.conduct.enraged.unwrap_or(1.);
+        if let Some(multiplier) = self.conduct.charging.take() {
+            log.push(warning(format!(
+                "The {} unleashes its charged attack!",
+                self.name
+            )));
+            return Some(rage * multiplier);
+        }
+
+        let health = self.cur_health as f64 / self.max_health as f64;
+        for behavior in &self.behaviors {
+            match *behavior {
+                Behavior::Flee { below, chance }
+                    if health < below && math::bool_from_chance(rng, chance) =>
+                {
+                    self.conduct.fled = true;
+                    log.push(format!("The {} fled!", self.name));
+                    return None;
+                }
+                Behavior::Heal { below, share, uses }
+                    if health < below && self.conduct.heals_used < uses =>
+                {
+                    self.conduct.heals_used += 1;
+                    let amount = (self.max_health as f64 * share).round() as u16;
+                    let healed = amount.min(self.max_health - self.cur_health);
+                    self.cur_health += healed;
+                    log.push(format!("The {} healed {healed} HP", self.name));
+                    return None;
+                }
+                Behavior::Enrage { below, multiplier }
+                    if health < below && self.conduct.enraged.is_none() =>
+                {
+                    self.conduct.enraged = Some(multiplier);
+                    log.push(warning(format!("The {} is enraged!", self.name)));
+                }
+                Behavior::Charge { chance, multiplier } if math::bool_from_chance(rng, chance) => {
+                    self.conduct.charging = Some(multiplier);
+                    log.push(warning(format!(
+                        "The {} is winding up a big attack...",
+                        self.name
+                    )));
+                    return None;
+                }
+                Behavior::CallForHelp {
+                    ref ally,
+                    below,
+                    chance,
+                } if health < below
+                    && !self.conduct.called_for_help
+                    && math::bool_from_chance(rng, chance) =>
+                {
+                    self.conduct.called_for_help = true;
+                    self.conduct.ally = Some(ally.clone());
+                    log.push(warning(format!(
+                        "The {} called for help, a(n) {ally} is on its way!",
+                        self.name
+                    )));
+                    return None;
+                }
+                _ => {}
+            }
+        }
+        Some(self.conduct.enraged.unwrap_or(1.))
+    }
 }
 
-fn ent_sprite(name: &str, plr: &Player) {
+/// The player attacks the first enemy still standing, then every enemy takes its turn.
+/// Shared with the balance simulator so it always matches real fights
+pub fn attack_round(group: &mut Vec<Entity>, plr: &mut Player) {
+    let mut log = Log::new();
+    if start_player_turn(plr, &mut log)
+        && let Some(target) = group.iter_mut().find(|entity| entity.in_fight())
+    {
+        player_attack(target, plr, 1., &mut log);
+    }
+    plr.statuses.end_turn();
+
+    enemy_turns(group, plr, &mut log);
+}
+
+/// Every enemy still in the fight takes its turn, then whoever they called for help joins in
+fn enemy_turns(group: &mut Vec<Entity>, plr: &mut Player, log: &mut Log) {
+    for entity in group.iter_mut().filter(|entity| entity.in_fight()) {
+        if !plr.is_alive() {
+            return;
+        }
+        entity_turn(entity, plr, log);
+    }
+
+    let called: Vec<String> = group
+        .iter_mut()
+        .filter_map(|entity| entity.conduct.ally.take())
+        .collect();
+    for name in called {
+        if let Some(ally) = plr.cur_place.spawn(&name, &mut plr.rng, plr.level) {
+            log.push(warning(format!("A(n) {name} joined the fight!")));
+            group.push(ally);
+        }
+    }
+}
+
+fn warning(msg: String) -> String {
+    paint_text(msg, "Red")
+}
+
+/// Ticks a combatant's statuses as their turn starts. Returns whether they get to act
+fn start_turn(
+    who: &str,
+    statuses: &mut Statuses,
+    cur_health: &mut u16,
+    max_health: u16,
+    rng: &mut GameRng,
+    log: &mut Log,
+) -> bool {
+    let tick = statuses.start_turn(cur_health, max_health, rng);
+    if tick.damage > 0 {
+        log.push(warning(format!(
+            "{who} lost {} HP to ailments",
+            tick.damage
+        )));
+    }
+    if tick.healed > 0 {
+        log.push(format!("{who} regenerated {} HP", tick.healed));
+    }
+    if *cur_health == 0 {
+        return false;
+    }
+    if let Some(kind) = tick.lost_turn {
+        log.push(format!("{who} couldn't act ({kind})"));
+    }
+    tick.lost_turn.is_none()
+}
+
+fn start_player_turn(plr: &mut Player, log: &mut Log) -> bool {
+    let Player {
+        statuses,
+        cur_health,
+        max_health,
+        rng,
+        ..
+    } = plr;
+    start_turn("You", statuses, cur_health, *max_health, rng, log)
+}
+
+// A pinned entity loses its turn, but its statuses still tick and run down
+fn entity_turn(entity: &mut Entity, plr: &mut Player, log: &mut Log) {
+    let who = format!("The {}", entity.name);
+    let pinned = std::mem::take(&mut entity.conduct.pinned);
+    let Entity {
+        statuses,
+        cur_health,
+        max_health,
+        ..
+    } = entity;
+
+    let can_act = start_turn(&who, statuses, cur_health, *max_health, &mut plr.rng, log);
+    if can_act && !pinned {
+        entity.advance_phase(log);
+        if let Some(multiplier) = entity.behave(&mut plr.rng, log) {
+            entity.attack(plr, multiplier, log);
+        }
+    } else if !can_act && entity.is_alive() && entity.conduct.charging.take().is_some() {
+        log.push(format!("The {}'s charge was interrupted", entity.name));
+    }
+    entity.statuses.end_turn();
+}
+
+fn hit_verb(outcome: HitOutcome) -> Option<&'static str> {
+    match outcome {
+        HitOutcome::Hit => Some("hit"),
+        HitOutcome::Crit => Some("critically hit"),
+        HitOutcome::Miss | HitOutcome::Dodge => None,
+    }
+}
+
+/// Returns the damage dealt
+fn player_attack(entity: &mut Entity, plr: &mut Player, multiplier: f64, log: &mut Log) -> u16 {
+    let (outcome, dealt) = plr.strike(entity, multiplier);
+    let msg = match hit_verb(outcome) {
+        Some(verb) => format!(
+            "[{outcome}] You {verb} the {} for {dealt} damage",
+            entity.name
+        ),
+        None if outcome == HitOutcome::Dodge => {
+            format!("[{outcome}] The {} dodged your attack", entity.name)
+        }
+        None => format!("[{outcome}] You missed the {}", entity.name),
+    };
+    log.push(if outcome == HitOutcome::Crit {
+        paint_text(msg, "Green")
+    } else {
+        msg
+    });
+    dealt
+}
+
+/// e.g., entities/forest_entities/mud_wizard.ans
+pub fn sprite_path(name: &str, place_name: &str) -> String {
     let ent = name.replace(" ", "_").to_lowercase();
-    let place = plr.cur_place.name.to_lowercase();
-
-    show_sprite(format!("entities/{place}_entities/{ent}.ans"));
-}
-
-fn panel_builder(_: (), cur_hp: u16, max_hp: u16, name: &str, tabs: usize) {
-    let t = "\t".repeat(tabs);
-    println!(
-        "
-    {t}{}{}{} 
-    {t}{}
-    {t}\t{}/{} HP
-    ",
-        style("Bold"),
-        name,
-        style("Reset"),
-        progress_bar(cur_hp, max_hp, "Green", 10),
-        cur_hp,
-        max_hp
-    );
+    let place = place_name.to_lowercase();
+
+    format!("entities/{place}_entities/{ent}.ans")
 }
 
-fn display_fight(entity: &Entity, plr: &Player) {
-    panel_builder(
-        clear_terminal(),
-        entity.cur_health,
-        entity.max_health,
-        &entity.name,
-        0,
-    );
+fn ent_sprite(name: &str, plr: &Player) {
+    show_sprite(sprite_path(name, &plr.cur_place.name));
+}
 
+fn panel_builder(
+    cur_hp: u16,
+    max_hp: u16,
+    stamina: Option<(u16, u16)>,
+    statuses: &[Status],
+    name: &str,
+    highlight: Option<&str>,
+    tabs: usize,
+) {
+    frontend::show_panel(&Panel {
+        title: name,
+        highlight,
+        cur: cur_hp,
+        max: max_hp,
+        stamina,
+        statuses,
+        indent: tabs,
+    });
+}
+
+fn display_fight(group: &[Entity], plr: &Player, log: &Log) {
+    clear_terminal();
+    for entity in group.iter().filter(|entity| !entity.conduct.fled) {
+        let statuses = entity.statuses.list();
+        panel_builder(
+            entity.cur_health,
+            entity.max_health,
+            None,
+            statuses,
+            &entity.name,
+            entity.rank.color(),
+            0,
+        );
+    }
+
+    // Whoever the player is up against first, or the last one standing
+    let shown = group.iter().find(|entity| entity.in_fight());
+    if let Some(entity) = shown.or(group.last()) {
+        ent_sprite(&entity.base_name, plr);
+    }
+    let stamina = Some((plr.cur_stamina, plr.max_stamina));
+    let statuses = plr.statuses.list();
     panel_builder(
-        ent_sprite(&entity.name, plr),
         plr.cur_health,
         plr.max_health,
+        stamina,
+        statuses,
         &plr.name,
+        None,
         7,
     );
+
+    for line in log {
+        show!("{line}");
+    }
 }
 
-fn encounter(entity: &mut Entity, plr: &mut Player) {
-    let options = ["Attack", "Inventory", "Flee"];
-    let dropped_item = math::bool_from_chance(0.1);
+#[derive(Clone, Copy)]
+enum Action {
+    Attack,
+    Ability(Ability),
+    Skills,
+    Skill(Skill), // Picked from `Skills`
+    Items,
+    Item(usize), // Inventory slot, picked from `Items`
+    Flee(f64),   // Chance to get away
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Attack => write!(f, "Attack"),
+            Self::Ability(ability) => write!(f, "{ability} ({})", ability.desc()),
+            Self::Skills => write!(f, "Skills"),
+            Self::Skill(skill) => write!(f, "{skill}"),
+            Self::Items => write!(f, "Items"),
+            Self::Item(_) => write!(f, "Item"),
+            Self::Flee(chance) => write!(f, "Flee ({:.0}% chance)", chance * 100.),
+        }
+    }
+}
+
+impl Action {
+    /// Whether it's aimed at one of the enemies
+    fn targets(&self) -> bool {
+        match self {
+            Self::Attack => true,
+            Self::Ability(ability) => *ability != Ability::Mend,
+            Self::Skill(skill) => *skill != Skill::Defend,
+            Self::Skills | Self::Items | Self::Item(_) | Self::Flee(_) => false,
+        }
+    }
 
-    'MainLoop: while entity.is_alive() && plr.is_alive() {
-        display_fight(entity, plr);
-        list_items(&options);
+    // Abilities only show up while they're still unused this fight, fleeing while nobody
+    // in the fight prevents it
+    fn get_available(plr: &Player, ability_used: bool, flee_chance: Option<f64>) -> Vec<Self> {
+        let mut actions = vec![Self::Attack];
+        if let Some(class) = plr.class
+            && !ability_used
+        {
+            actions.push(Self::Ability(class.ability()));
+        }
+        if !Skill::unlocked(plr.level).is_empty() {
+            actions.push(Self::Skills);
+        }
+        if plr.inventory.iter().any(|(itm, _)| itm.is_usable()) {
+            actions.push(Self::Items);
+        }
+        if let Some(chance) = flee_chance {
+            actions.push(Self::Flee(chance));
+        }
+        actions
+    }
+}
 
-        'InputLoop: loop {
-            let input: String = prompt!("What would you like to do? Enter (1-{}): ", options.len());
-            match input.as_str() {
-                "1" => break 'InputLoop plr.attack(entity),
-                "2" => {
-                    view_inventory(plr);
-                    continue 'MainLoop;
+/// Uses the class ability in place of an attack
+fn use_ability(ability: Ability, entity: &mut Entity, plr: &mut Player, log: &mut Log) {
+    match ability {
+        Ability::Cleave => {
+            player_attack(entity, plr, 1., log);
+            player_attack(entity, plr, 1., log);
+        }
+        Ability::PinningShot => {
+            player_attack(entity, plr, 1., log);
+            if entity.is_alive() {
+                entity.conduct.pinned = true;
+                log.push(format!("The {} is pinned down!", entity.name));
+            }
+        }
+        Ability::Mend => {
+            let share = |of: f64| (plr.max_health as f64 * of).round() as u16;
+            let regen = Status::new(StatusKind::Regen, MEND_REGEN_TURNS, share(MEND_REGEN_SHARE));
+            let healed = plr.heal(share(MEND_SHARE));
+            plr.statuses.apply(regen);
+            log.push(format!("You mended {healed} HP"));
+        }
+    }
+}
+
+/// Lets the player pick one of their skills, `None` if they back out
+fn choose_skill(plr: &Player) -> Option<Skill> {
+    let skills = Skill::unlocked(plr.level);
+    let labels: Vec<String> = skills
+        .iter()
+        .map(|skill| format!("{skill} [{} SP] ({})", skill.cost(), skill.desc()))
+        .collect();
+
+    let prompt = format!(
+        "Use which skill? Enter (1-{}) or press `enter` to go back: ",
+        skills.len()
+    );
+    loop {
+        let skill = skills[frontend::ask_choice(&prompt, &labels, true)?];
+        if skill.cost() <= plr.cur_stamina {
+            return Some(skill);
+        }
+        warn!(
+            "Not enough stamina, {skill} needs {} SP and you have {}",
+            skill.cost(),
+            plr.cur_stamina
+        );
+    }
+}
+
+/// Only what can be used mid-fight, i.e., healers and specials
+fn choose_item(plr: &Player) -> Option<usize> {
+    let usable: Vec<usize> = (0..plr.inventory.len())
+        .filter(|&loc| plr.inventory[loc].0.is_usable())
+        .collect();
+    let labels: Vec<String> = usable
+        .iter()
+        .map(|&loc| {
+            let (itm, qty) = &plr.inventory[loc];
+            match itm.item_type {
+                ItemType::Healer { amount } => {
+                    format!("{} (x{qty}) (Heals {amount} HP)", itm.painted_name())
                 }
-                "3" => return drop(prompt!("You fled. Press `enter` to continue ")),
-                _ => warn!("Invalid input, try again"),
+                _ => format!("{} (x{qty}) ({})", itm.painted_name(), itm.desc),
             }
-        } // Attack the player if the entity is still alive:
-        if entity.is_alive() {
-            entity.attack(plr);
+        })
+        .collect();
+
+    let prompt = format!(
+        "Use which item? Enter (1-{}) or press `enter` to go back: ",
+        usable.len()
+    );
+    Some(usable[frontend::ask_choice(&prompt, &labels, true)?])
+}
+
+/// Uses an item in place of an attack
+fn use_item(loc: usize, plr: &mut Player, log: &mut Log) {
+    let itm = plr.inventory[loc].0.clone();
+    let before = plr.cur_health;
+    let cured = itm.use_item(plr, loc);
+
+    let healed = plr.cur_health.saturating_sub(before);
+    if healed > 0 {
+        log.push(format!("You used a(n) {} and healed {healed} HP", itm.name));
+    } else {
+        log.push(format!("You used a(n) {}", itm.name));
+    }
+    if cured {
+        log.push(String::from("Your ailments fade"));
+    }
+}
+
+/// Uses a skill in place of an attack
+fn use_skill(skill: Skill, entity: &mut Entity, plr: &mut Player, log: &mut Log) {
+    plr.cur_stamina -= skill.cost();
+    match skill {
+        Skill::HeavyStrike => {
+            player_attack(entity, plr, HEAVY_STRIKE_MULTIPLIER, log);
+        }
+        Skill::Defend => {
+            let shield = Status::new(StatusKind::Shielded, 1, 0);
+            plr.statuses.apply(shield);
+            log.push(String::from("You brace yourself..."));
+        }
+        Skill::Drain => {
+            let dealt = player_attack(entity, plr, 1., log);
+            let healed = plr.heal((dealt as f64 * DRAIN_SHARE).round() as u16);
+            log.push(format!("You drained {healed} HP from the {}", entity.name));
+        }
+        Skill::Stun => {
+            let stun = Status::new(StatusKind::Stun, STUN_TURNS, 0);
+            entity.statuses.apply(stun);
+            log.push(format!("The {} is stunned!", entity.name));
         }
     }
-    display_fight(entity, plr);
+}
 
-    if !plr.is_alive() {
+/// Lets the player pick which enemy to go after, `None` if they back out
+fn choose_target(group: &[Entity]) -> Option<usize> {
+    let targets: Vec<usize> = (0..group.len())
+        .filter(|&ind| group[ind].in_fight())
+        .collect();
+    if let [only] = targets[..] {
+        return Some(only);
+    }
+
+    let labels: Vec<String> = targets
+        .iter()
+        .map(|&ind| {
+            let entity = &group[ind];
+            format!(
+                "{} ({}/{} HP)",
+                entity.name, entity.cur_health, entity.max_health
+            )
+        })
+        .collect();
+    let prompt = format!(
+        "Target which enemy? Enter (1-{}) or press `enter` to go back: ",
+        targets.len()
+    );
+    Some(targets[frontend::ask_choice(&prompt, &labels, true)?])
+}
+
+/// Better with agility and levels, worse the more enemies are still after the player.
+/// `None` if one of them won't let the player go at all
+fn flee_chance(plr: &Player, group: &[Entity]) -> Option<f64> {
+    let chasers: Vec<&Entity> = group.iter().filter(|entity| entity.in_fight()).collect();
+    if chasers.iter().any(|entity| !entity.fleeable) {
+        return None;
+    }
+
+    let pursuit: f64 = chasers.iter().map(|entity| entity.pursuit).sum();
+    let chance = plr.attributes.flee_chance() + plr.level as f64 * LEVEL_FLEE - pursuit;
+    Some(chance.clamp(MIN_FLEE_CHANCE, 1.))
+}
+
+/// Running off in a hurry may cost the player something from their inventory, never a key
+fn drop_while_fleeing(plr: &mut Player) {
+    let droppable: Vec<usize> = (0..plr.inventory.len())
+        .filter(|&loc| plr.inventory[loc].0.item_type != ItemType::Key)
+        .collect();
+    if droppable.is_empty() || !math::bool_from_chance(&mut plr.rng, FLEE_DROP_CHANCE) {
         return;
     }
-    plr.update_xp(entity);
 
-    if dropped_item {
-        let msg = format!("\nThe {} also dropped a(n)", entity.name);
-        plr.fetch_drop(plr.cur_place.get_drops(), &msg);
+    let loc = droppable[rng_from_range(&mut plr.rng, (0, droppable.len() - 1))];
+    let name = plr.inventory[loc].0.name.clone();
+    plr.remove_from_inventory(loc, 1);
+    warn!("You dropped a(n) {name} while fleeing!");
+}
+
+/// Asks until the player does something that takes their turn. Returns whether they got away
+fn player_action(
+    group: &mut [Entity],
+    plr: &mut Player,
+    ability_used: &mut bool,
+    log: &mut Log,
+) -> bool {
+    let (action, target) = loop {
+        display_fight(group, plr, log);
+
+        let actions = Action::get_available(plr, *ability_used, flee_chance(plr, group));
+        let labels: Vec<String> = actions.iter().map(|a| a.to_string()).collect();
+        let prompt = format!("What would you like to do? Enter (1-{}): ", actions.len());
+
+        let ind = frontend::ask_choice(&prompt, &labels, false).unwrap_or_default();
+        let action = match actions[ind] {
+            Action::Skills => match choose_skill(plr) {
+                Some(skill) => Action::Skill(skill),
+                None => continue,
+            },
+            Action::Items => match choose_item(plr) {
+                Some(loc) => Action::Item(loc),
+                None => continue,
+            },
+            action => action,
+        };
+
+        if !action.targets() {
+            break (action, None);
+        }
+        if let Some(target) = choose_target(group) {
+            break (action, Some(target));
+        }
+    };
+    log.clear();
+
+    // Untargeted actions still get someone, but never touch them
+    let target = target.or_else(|| group.iter().position(|entity| entity.in_fight()));
+    let entity = &mut group[target.unwrap_or_default()];
+    match action {
+        Action::Attack => {
+            player_attack(entity, plr, 1., log);
+        }
+        Action::Ability(ability) => {
+            *ability_used = true;
+            use_ability(ability, entity, plr, log);
+        }
+        Action::Skill(skill) => use_skill(skill, entity, plr, log),
+        Action::Item(loc) => use_item(loc, plr, log),
+        Action::Flee(chance) => {
+            if math::bool_from_chance(&mut plr.rng, chance) {
+                return true;
+            }
+            log.push(warning(format!("The {} cut you off!", entity.name)));
+        }
+        Action::Skills | Action::Items => unreachable!("Handled while choosing"),
+    }
+    false
+}
+
+/// Fights a group of enemies until they're all beaten or gone, or the player is.
+/// Hands the group back as it ended up, whoever joined mid-fight included
+pub fn encounter(mut group: Vec<Entity>, plr: &mut Player) -> Vec<Entity> {
+    let mut ability_used = false;
+    let mut log = Log::new();
+
+    while group.iter().any(Entity::in_fight) && plr.is_alive() {
+        if !start_player_turn(plr, &mut log) {
+            display_fight(&group, plr, &log);
+            prompt!("Press `enter` to continue ");
+            log.clear();
+        } else if player_action(&mut group, plr, &mut ability_used, &mut log) {
+            plr.statuses.clear();
+            drop_while_fleeing(plr);
+            prompt!("You fled. Press `enter` to continue ");
+            return group;
+        }
+        plr.statuses.end_turn();
+
+        enemy_turns(&mut group, plr, &mut log);
+    }
+    plr.statuses.clear();
+    display_fight(&group, plr, &log);
+
+    if !plr.is_alive() {
+        return group;
+    }
+    for entity in group.iter().filter(|entity| entity.conduct.fled) {
+        show!("\nThe {} got away", entity.name);
+    }
+
+    let defeated: Vec<&Entity> = group.iter().filter(|entity| !entity.is_alive()).collect();
+    if !defeated.is_empty() {
+        plr.update_xp(&defeated);
+
+        for entity in defeated {
+            // Elites and champions always leave something behind
+            let drops = entity.rank != Rank::Normal;
+            if drops || math::bool_from_chance(&mut plr.rng, entity.drop_chance) {
+                let msg = format!("\nThe {} also dropped a(n)", entity.name);
+                plr.fetch_drop(entity.get_drops(&plr.cur_place), &msg);
+            }
+        }
+        plr.display_leveling();
     }
-    plr.display_leveling();
     prompt!("\nPress `enter` to continue ");
+    group
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::math::GameRng;
+
+    #[test]
+    fn encounter_until_the_enemy_falls() {
+        // Keeps attacking, the rest answers "Press `enter`" prompts
+        let transcript = frontend::script(&["1"; 20]);
+        let mut plr = Player::new(String::from("Tess"));
+        plr.rng = GameRng::seeded(7);
+        let dummy = Entity::new("Dummy", 30, (1, 1));
+
+        encounter(vec![dummy], &mut plr);
+
+        let transcript = transcript.borrow();
+        assert!(transcript.iter().any(|line| line.contains("[1] >> Attack")));
+        assert!(
+            transcript
+                .iter()
+                .any(|line| line.contains("You hit the Dummy"))
+        );
+        assert!(
+            transcript
+                .iter()
+                .any(|line| line.contains("You defeated the Dummy"))
+        );
+        assert!(plr.is_alive());
+        assert!(plr.xp > 0.);
+    }
+
+    #[test]
+    fn encounter_hands_back_whoever_got_away() {
+        frontend::script(&["1"; 20]);
+        let mut plr = Player::new(String::from("Tess"));
+        plr.rng = GameRng::seeded(7);
+        let mut coward = Entity::new("Coward", 500, (1, 1));
+        coward.behaviors = vec![Behavior::Flee {
+            below: 1.,
+            chance: 1.,
+        }];
+
+        let group = encounter(vec![coward], &mut plr);
+
+        assert!(group[0].conduct.fled);
+        assert!(group[0].is_alive());
+    }
+
+    #[test]
+    fn healing_mid_fight_cures_and_says_so() {
+        frontend::script(&[]);
+        let mut plr = Player::new(String::from("Tess"));
+        let apple = content::get().item("Apple").unwrap().clone();
+        plr.add_to_inventory(apple, 1);
+        plr.statuses.apply(Status::new(StatusKind::Poison, 3, 5));
+
+        let mut log = Log::new();
+        use_item(0, &mut plr, &mut log);
+
+        assert!(!plr.statuses.has(StatusKind::Poison));
+        assert_eq!(log.last().map(String::as_str), Some("Your ailments fade"));
+    }
+
+    #[test]
+    fn pinned_enemies_still_tick() {
+        frontend::script(&[]);
+        let mut plr = Player::new(String::from("Tess"));
+        let mut pinned = Entity::new("Dummy", 50, (10, 10));
+        pinned.conduct.pinned = true;
+        pinned.statuses.apply(Status::new(StatusKind::Poison, 2, 5));
+
+        let mut group = vec![pinned];
+        enemy_turns(&mut group, &mut plr, &mut Log::new());
+
+        let dummy = &group[0];
+        assert_eq!(plr.cur_health, plr.max_health);
+        assert_eq!(dummy.cur_health, 45);
+        assert_eq!(dummy.statuses.list()[0].turns, 1);
+        assert!(!dummy.conduct.pinned);
+    }
 }
@@ -0,0 +1,152 @@
+//! Everything the game shows or asks goes through the current `Frontend`,
+//! so the game logic never has to know whether there's a human at a TTY
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use crate::recording;
+use crate::status::Status;
+
+pub mod scripted;
+pub mod terminal;
+
+pub use scripted::Scripted;
+pub use terminal::Terminal;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Tone {
+    Plain,
+    Info,    // `inform!`
+    Warning, // `warn!`
+}
+
+/// A health bar with a name over it, e.g., either side of a fight
+pub struct Panel<'a> {
+    pub title: &'a str,
+    pub highlight: Option<&'a str>, // Color of the title, plain when unset
+    pub cur: u16,
+    pub max: u16,
+    pub stamina: Option<(u16, u16)>, // Current and max, only the player has any
+    pub statuses: &'a [Status],
+    pub indent: usize, // In tabs
+}
+
+pub trait Frontend {
+    /// Asks for a line of input, trimmed
+    fn ask_text(&mut self, prompt: &str) -> String;
+
+    fn show_message(&mut self, msg: &str, tone: Tone);
+
+    /// `path` is relative to the sprites directory, e.g., places/forest.ans
+    fn show_sprite(&mut self, path: &str);
+
+    fn show_panel(&mut self, panel: &Panel);
+
+    fn clear(&mut self);
+
+    /// For dramatic effect; frontends without a human can skip it
+    fn pause(&mut self, _duration: Duration) {}
+
+    /// Lists `options` and asks until one is picked. Returns its index,
+    /// or `None` if `can_leave` and the player just pressed `enter`
+    fn ask_choice(&mut self, prompt: &str, options: &[String], can_leave: bool) -> Option<usize> {
+        for (i, opt) in options.iter().enumerate() {
+            self.show_message(&list_entry(i + 1, opt), Tone::Plain);
+        }
+
+        loop {
+            let inp = self.ask_text(prompt);
+            if can_leave && inp.is_empty() {
+                return None;
+            }
+            match inp.parse::<usize>() {
+                Ok(num) if (1..=options.len()).contains(&num) => return Some(num - 1),
+                _ => self.show_message("Invalid input, please try again", Tone::Warning),
+            }
+        }
+    }
+}
+
+/// [[n]] >> ToDisplay
+pub fn list_entry(num: usize, opt: &str) -> String {
+    use crate::utilities::color;
+    // Painted parts of `opt` (e.g., an item's rarity) end in a reset, pick the entry's color back up
+    let reset = color("Reset").to_string();
+    let entry_clr = color("LightMagenta").to_string();
+    let opt = opt.replace(&reset, &format!("{reset}{entry_clr}"));
+    format!("{}[{num}] >> {entry_clr}{opt}{reset}", color("Cyan"))
+}
+
+thread_local! {
+    static FRONTEND: RefCell<Box<dyn Frontend>> = RefCell::new(Box::new(Terminal));
+}
+
+/// Swaps in a new frontend for this thread, handing back the old one
+pub fn set(frontend: Box<dyn Frontend>) -> Box<dyn Frontend> {
+    FRONTEND.replace(frontend)
+}
+
+fn with<R>(f: impl FnOnce(&mut dyn Frontend) -> R) -> R {
+    FRONTEND.with_borrow_mut(|frontend| f(frontend.as_mut()))
+}
+
+// Both ask functions hand the answer to `recording`, so sessions can be replayed
+
+pub fn ask_text(prompt: &str) -> String {
+    let answer = with(|fe| fe.ask_text(prompt));
+    recording::record(&answer);
+    answer
+}
+
+pub fn ask_choice(prompt: &str, options: &[String], can_leave: bool) -> Option<usize> {
+    let choice = with(|fe| fe.ask_choice(prompt, options, can_leave));
+    recording::record(&choice.map_or(String::new(), |ind| (ind + 1).to_string()));
+    choice
+}
+
+pub fn show_message(msg: &str, tone: Tone) {
+    with(|fe| fe.show_message(msg, tone))
+}
+
+pub fn show_sprite(path: &str) {
+    with(|fe| fe.show_sprite(path))
+}
+
+pub fn show_panel(panel: &Panel) {
+    with(|fe| fe.show_panel(panel))
+}
+
+pub fn clear() {
+    with(|fe| fe.clear())
+}
+
+pub fn pause(duration: Duration) {
+    with(|fe| fe.pause(duration))
+}
+
+/// Loads content and swaps in a `Scripted` frontend fed `inputs`, handing back its transcript
+#[cfg(test)]
+pub fn script(inputs: &[&str]) -> std::rc::Rc<RefCell<Vec<String>>> {
+    if let Err(er) = crate::content::init() {
+        panic!("Loading content failed: {er}");
+    }
+    let script = Scripted::new(inputs.iter().copied());
+    let transcript = script.transcript();
+    set(Box::new(script));
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::{color, paint_text};
+
+    #[test]
+    fn list_entries_keep_their_color_past_painted_names() {
+        let name = paint_text("Fairy Milk Bottle", "Green");
+        let entry = list_entry(1, &format!("{name} (x2)"));
+
+        let after_name = format!("{}{} (x2)", color("Reset"), color("LightMagenta"));
+        assert!(entry.contains(&after_name));
+    }
+}
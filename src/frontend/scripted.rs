@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::*;
+
+/// Plays back canned input and keeps everything shown in memory, so the game
+/// can be driven without a human at a TTY
+pub struct Scripted {
+    inputs: VecDeque<String>,
+    transcript: Rc<RefCell<Vec<String>>>,
+    live: Option<Box<dyn Frontend>>,
+}
+
+impl Scripted {
+    pub fn new<I, S>(inputs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            transcript: Rc::default(),
+            live: None,
+        }
+    }
+
+    /// Also shows everything on `live`, which takes over asking once the script runs out
+    pub fn showing_on(mut self, live: Box<dyn Frontend>) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    /// Shared handle to every line shown so far, with colors stripped.
+    /// Stays readable after the frontend is handed to `frontend::set`
+    pub fn transcript(&self) -> Rc<RefCell<Vec<String>>> {
+        Rc::clone(&self.transcript)
+    }
+
+    fn record(&self, line: String) {
+        self.transcript.borrow_mut().push(strip_ansi(&line));
+    }
+}
+
+impl Frontend for Scripted {
+    fn ask_text(&mut self, prompt: &str) -> String {
+        let Some(inp) = self.inputs.pop_front() else {
+            let Some(live) = &mut self.live else {
+                panic!("Scripted input ran out at `{}`", strip_ansi(prompt));
+            };
+            return live.ask_text(prompt);
+        };
+        self.record(format!("{prompt}{inp}"));
+
+        if let Some(live) = &mut self.live {
+            live.show_message(&format!("\n{prompt}{inp}"), Tone::Info);
+            if self.inputs.is_empty() {
+                live.show_message("\n(End of script, you're in control now)", Tone::Warning);
+            }
+        }
+        inp.trim().to_string()
+    }
+
+    fn show_message(&mut self, msg: &str, tone: Tone) {
+        self.record(msg.to_owned());
+        if let Some(live) = &mut self.live {
+            live.show_message(msg, tone);
+        }
+    }
+
+    fn show_sprite(&mut self, path: &str) {
+        self.record(format!("[sprite: {path}]"));
+        if let Some(live) = &mut self.live {
+            live.show_sprite(path);
+        }
+    }
+
+    fn show_panel(&mut self, panel: &Panel) {
+        let mut line = format!("{} {}/{} HP", panel.title, panel.cur, panel.max);
+        if let Some((cur, max)) = panel.stamina {
+            line.push_str(&format!(", {cur}/{max} SP"));
+        }
+        for status in panel.statuses {
+            line.push_str(&format!(", {status}"));
+        }
+        self.record(line);
+        if let Some(live) = &mut self.live {
+            live.show_panel(panel);
+        }
+    }
+
+    fn clear(&mut self) {
+        if let Some(live) = &mut self.live {
+            live.clear();
+        }
+    }
+
+    fn pause(&mut self, duration: Duration) {
+        // Only worth waiting for once a human is back in control
+        if let Some(live) = &mut self.live
+            && self.inputs.is_empty()
+        {
+            live.pause(duration);
+        }
+    }
+}
+
+// Drops escape sequences like `\x1b[38;5;4m` so transcripts are plain text
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
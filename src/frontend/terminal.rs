@@ -0,0 +1,88 @@
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use super::*;
+use crate::utilities::*;
+
+/// The real thing: termion colors in, `stdin` out
+pub struct Terminal;
+
+impl Frontend for Terminal {
+    fn ask_text(&mut self, prompt: &str) -> String {
+        input!("\n{}{}{}", color("Blue"), prompt, color("Reset"))
+            .trim()
+            .to_string()
+    }
+
+    fn show_message(&mut self, msg: &str, tone: Tone) {
+        match tone {
+            Tone::Plain => println!("{msg}"),
+            Tone::Info => println!("{}{}{}", color("Blue"), msg, color("Reset")),
+            Tone::Warning => println!(
+                "{}{}{}{}{}",
+                color("Red"),
+                style("Italics"),
+                msg,
+                color("Reset"),
+                style("Reset")
+            ),
+        }
+    }
+
+    fn show_sprite(&mut self, path: &str) {
+        match fs::read_to_string(format!("{SPRITE_DIR}/{path}")) {
+            Ok(buffer) => println!("{buffer}"),
+            Err(er) => eprintln!("Loading sprite failed: `{er}`"),
+        }
+    }
+
+    fn show_panel(&mut self, panel: &Panel) {
+        let t = "\t".repeat(panel.indent);
+        println!(
+            "
+    {t}{}{}{} 
+    {t}{}
+    {t}\t{}/{} HP
+    ",
+            style("Bold"),
+            match panel.highlight {
+                Some(clr) => paint_text(panel.title, clr),
+                None => panel.title.to_owned(),
+            },
+            style("Reset"),
+            progress_bar(panel.cur, panel.max, "Green", 10),
+            panel.cur,
+            panel.max
+        );
+        if let Some((cur, max)) = panel.stamina {
+            println!(
+                "    {t}{}\n    {t}\t{cur}/{max} SP\n",
+                progress_bar(cur, max, "Yellow", 10)
+            );
+        }
+        if !panel.statuses.is_empty() {
+            let statuses: Vec<String> = panel
+                .statuses
+                .iter()
+                .map(|status| {
+                    let clr = if status.kind.is_harmful() {
+                        "Red"
+                    } else {
+                        "Green"
+                    };
+                    paint_text(status, clr)
+                })
+                .collect();
+            println!("    {t}{}\n", statuses.join(", "));
+        }
+    }
+
+    fn clear(&mut self) {
+        print!("{}[2J", 27 as char);
+    }
+
+    fn pause(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
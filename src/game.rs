@@ -1,12 +1,20 @@
 use std::fmt::Display;
-use std::{thread, time::Duration};
+use std::time::Duration;
 
 use crate::inform;
 use crate::prompt;
+use crate::show;
 use crate::warn;
 
+use super::attributes::Attribute;
+use super::classes::Class;
+use super::entities;
+use super::frontend;
 use super::places::*;
 use super::player::*;
+use super::recording;
+use super::save;
+use super::skills::Skill;
 
 use Location as Loc;
 
@@ -18,20 +26,24 @@ use super::utilities::*;
 #[derive(Clone, Copy)]
 enum Options {
     Explore,
+    ChallengeBoss,
     Travel,
     Meditate,
     ViewInventory,
     ViewStats,
+    SaveGame,
 }
 
 impl Display for Options {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Explore => write!(f, "Explore"),
+            Self::ChallengeBoss => write!(f, "Challenge Boss"),
             Self::Travel => write!(f, "Travel"),
             Self::Meditate => write!(f, "Meditate"),
             Self::ViewInventory => write!(f, "View Inventory"),
             Self::ViewStats => write!(f, "View Stats"),
+            Self::SaveGame => write!(f, "Save Game"),
         }
     }
 }
@@ -39,21 +51,56 @@ impl Options {
     pub fn get_registered() -> Vec<Self> {
         vec![
             Self::Explore,
+            Self::ChallengeBoss,
             Self::Travel,
             Self::Meditate,
             Self::ViewInventory,
             Self::ViewStats,
+            Self::SaveGame,
         ]
     }
 }
 
-pub fn run_game() {
-    let mut plr: Player = setup_plr();
+/// `seed` makes every roll of the session reproducible, otherwise one is picked at random
+pub fn run_game(seed: Option<u64>) {
+    let (mut plr, slot) = setup_plr();
+    if let Some(seed) = seed {
+        plr.rng = math::GameRng::seeded(seed);
+    }
     // plr.silly(); // Add junk to inventory
 
+    let recorded_to = recording::start(&plr)
+        .inspect_err(|er| warn!("Recording this session failed: {er}"))
+        .ok();
+
+    play(&mut plr, Some(&slot));
+    recording::stop();
+
+    if let Some(path) = recorded_to {
+        inform!("A replay of this session was saved to `{}`", path.display());
+    }
+}
+
+/// Plays a recorded session back from where it started, then hands control to the player
+pub fn run_recording(rec: recording::Recording) {
+    let recording::Recording {
+        seed,
+        mut player,
+        inputs,
+    } = rec;
+    player.rng = math::GameRng::seeded(seed);
+
+    let script = frontend::Scripted::new(inputs).showing_on(Box::new(frontend::Terminal));
+    frontend::set(Box::new(script));
+
+    play(&mut player, None); // Nothing's saved while replaying
+    frontend::set(Box::new(frontend::Terminal));
+}
+
+fn play(plr: &mut Player, slot: Option<&str>) {
     while plr.is_alive() {
         let plr_option: Options = get_option();
-        carry_option(plr_option, &mut plr);
+        carry_option(plr_option, plr, slot);
     }
     warn!("You died...");
 }
@@ -74,8 +121,8 @@ pub fn replay() -> bool {
     }
 }
 
-fn setup_plr() -> Player {
-    println!(
+fn show_title() {
+    show!(
         r"{}    _      _             _                 
    /_\  __| |_ _____ _ _| |_ _  _ _ _ __ _ 
   / _ \/ _` \ V / -_) ' \  _| || | '_/ _` |
@@ -84,7 +131,181 @@ fn setup_plr() -> Player {
         color("LightGreen"),
         color("Reset")
     );
+}
+
+/// Returns the player along with the save slot they're playing on
+fn setup_plr() -> (Player, String) {
+    loop {
+        clear_terminal();
+        show_title();
+
+        let slots = save::slots();
+        if slots.is_empty() {
+            return new_game();
+        }
+
+        inform!("Save slots:\n");
+        let mut entries: Vec<String> = slots.iter().map(slot_entry).collect();
+        entries.extend(SLOT_ACTIONS.iter().map(|a| a.to_string()));
+
+        let prompt = format!("Type a matching number (1-{}): ", entries.len());
+        let chosen = frontend::ask_choice(&prompt, &entries, false).unwrap_or_default();
+
+        if let Some(slot) = slots.get(chosen) {
+            match load_slot(slot) {
+                Ok(plr) => {
+                    welcome(&plr.name, "Welcome back");
+                    return (plr, slot.name.clone());
+                }
+                Err(er) => {
+                    warn!("{er}");
+                    prompt!("Press `enter` to continue ");
+                    continue;
+                }
+            }
+        }
+
+        match SLOT_ACTIONS[chosen - slots.len()] {
+            "New Game" => return new_game(),
+            "Duplicate Slot" => duplicate_slot(&slots),
+            "Delete Slot" => delete_slot(&slots),
+            _ => {}
+        }
+    }
+}
+
+const SLOT_ACTIONS: &[&str] = &["New Game", "Duplicate Slot", "Delete Slot"];
+
+fn slot_entry(slot: &save::Slot) -> String {
+    let (info, verb) = match (&slot.autosave, &slot.info) {
+        (Some(auto), _) => (auto, "autosaved"),
+        (None, Ok(info)) => (info, "played"),
+        (None, Err(er)) => {
+            return format!("{}: {}({er}){}", slot.name, color("Red"), color("Reset"));
+        }
+    };
+    format!(
+        "{}: {} {}(Level {} in the {}, {verb} {}){}",
+        slot.name,
+        info.name,
+        style("Faint"),
+        info.level,
+        info.location,
+        time_ago(info.last_played),
+        style("Reset")
+    )
+}
+
+/// Offers to resume from the slot's autosave when it's ahead of the manual save
+fn load_slot(slot: &save::Slot) -> Result<Player, save::SaveError> {
+    let Some(auto) = &slot.autosave else {
+        return save::load(&slot.name);
+    };
+
+    loop {
+        let inp = prompt!(
+            "Resume from the autosave ({})? Enter (y/n): ",
+            time_ago(auto.last_played)
+        );
+        match inp.to_lowercase().as_str() {
+            "y" => break save::load_autosave(&slot.name),
+            "n" => break save::load(&slot.name),
+            _ => warn!("Invalid input"),
+        }
+    }
+}
 
+fn time_ago(timestamp: u64) -> String {
+    if timestamp == 0 {
+        return String::from("a while ago");
+    }
+    let mins = save::now().saturating_sub(timestamp) / 60;
+    match mins {
+        0 => String::from("just now"),
+        1..60 => format!("{mins} minute(s) ago"),
+        60..1440 => format!("{} hour(s) ago", mins / 60),
+        _ => format!("{} day(s) ago", mins / 1440),
+    }
+}
+
+/// Picks an existing slot, or `None` if the player backs out
+fn choose_slot<'a>(slots: &'a [save::Slot], action: &str) -> Option<&'a save::Slot> {
+    let len = slots.len();
+    loop {
+        let inp = prompt!(
+            "Which slot would you like to {action}? Enter (1-{len}) or `enter` to go back: "
+        );
+        if inp.is_empty() {
+            return None;
+        }
+        if let Some(ind) = indexize(&inp, len) {
+            return slots.get(ind);
+        }
+    }
+}
+
+fn duplicate_slot(slots: &[save::Slot]) {
+    let Some(from) = choose_slot(slots, "duplicate") else {
+        return;
+    };
+
+    loop {
+        let to = prompt!("Enter a name for the copy or `enter` to go back: ");
+        if to.is_empty() {
+            return;
+        }
+        match save::duplicate(&from.name, &to) {
+            Ok(()) => return,
+            Err(er) => warn!("{er}"),
+        }
+    }
+}
+
+fn delete_slot(slots: &[save::Slot]) {
+    let Some(slot) = choose_slot(slots, "delete") else {
+        return;
+    };
+
+    loop {
+        warn!("\nAre you sure you want to delete `{}`?", slot.name);
+        let inp = prompt!("This can't be undone 🤯 Enter (y/n): ");
+        match inp.to_lowercase().as_str() {
+            "y" => {
+                if let Err(er) = save::delete(&slot.name) {
+                    warn!("{er}");
+                    prompt!("Press `enter` to continue ");
+                }
+                return;
+            }
+            "n" => return,
+            _ => warn!("Invalid input"),
+        }
+    }
+}
+
+fn choose_class() -> Class {
+    let classes = Class::get_registered();
+    let labels: Vec<String> = classes
+        .iter()
+        .map(|class| {
+            let ability = class.ability();
+            format!(
+                "{class} - {}\n      {} HP, starts with a {}, ability: {ability} ({})",
+                class.desc(),
+                class.max_health(),
+                class.starting_weapon(),
+                ability.desc()
+            )
+        })
+        .collect();
+
+    inform!("\nChoose your class:\n");
+    let prompt = format!("Type a matching number (1-{}): ", classes.len());
+    let ind = frontend::ask_choice(&prompt, &labels, false).unwrap_or_default();
+    classes[ind]
+}
+
+fn new_game() -> (Player, String) {
     let plr_name: String = 'O: loop {
         let inp = prompt!("Please enter your name: ");
         const PROHIBITED_NAMES: &[&str] = &["god", "devil", "gabriel"];
@@ -103,17 +324,42 @@ fn setup_plr() -> Player {
             }
         }
     };
+    let plr = Player::with_class(plr_name, choose_class());
 
-    println!(
-        "\nWelcome, {}{}{plr_name}{}{}!\n",
+    let slot = loop {
+        let inp = prompt!(
+            "Name this save slot or press `enter` to use '{}': ",
+            plr.name
+        );
+        let slot = if inp.is_empty() {
+            plr.name.clone()
+        } else {
+            inp
+        };
+
+        if save::exists(&slot) {
+            warn!("{}", save::SaveError::SlotTaken(slot));
+            continue;
+        }
+        match save::save(&slot, &plr) {
+            Ok(()) => break slot,
+            Err(er) => warn!("{er}"),
+        }
+    };
+
+    welcome(&plr.name, "Welcome");
+    (plr, slot)
+}
+
+fn welcome(plr_name: &str, greeting: &str) {
+    show!(
+        "\n{greeting}, {}{}{plr_name}{}{}!\n",
         color("Cyan"),
         style("Bold"),
         color("Reset"),
         style("Reset")
     );
     prompt!("Press `enter` to continue ");
-
-    Player::new(plr_name)
 }
 
 fn get_option() -> Options {
@@ -121,26 +367,22 @@ fn get_option() -> Options {
     inform!("What would you like to do?\n");
 
     let options = Options::get_registered();
-    let len = options.len();
-    list_items(&options);
-
-    loop {
-        let inp: String = prompt!("Type a matching number (1-{len}): ");
-        let Some(ind) = indexize(&inp, len) else {
-            continue;
-        };
+    let labels: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+    let prompt = format!("Type a matching number (1-{}): ", options.len());
 
-        break options[ind];
-    }
+    let ind = frontend::ask_choice(&prompt, &labels, false).unwrap_or_default();
+    options[ind]
 }
 
-fn carry_option(opt: Options, plr: &mut Player) {
+fn carry_option(opt: Options, plr: &mut Player, slot: Option<&str>) {
     match opt {
-        Options::Explore => explore(plr),
-        Options::Travel => travel(plr),
+        Options::Explore => explore(plr, slot),
+        Options::ChallengeBoss => challenge_boss(plr, slot),
+        Options::Travel => travel(plr, slot),
         Options::Meditate => meditate(plr),
         Options::ViewInventory => view_inventory(plr),
         Options::ViewStats => view_stats(plr),
+        Options::SaveGame => save_game(plr, slot),
     }
 }
 
@@ -155,10 +397,10 @@ static GREEN: fn(&str) -> String = |s: &str| {
 };
 
 fn show_header(c: &str) {
-    println!("{}", GREEN(c))
+    show!("{}", GREEN(c))
 }
 
-fn explore(plr: &mut Player) {
+fn explore(plr: &mut Player, slot: Option<&str>) {
     clear_terminal();
     let p_name = plr.cur_place.name.to_lowercase();
 
@@ -167,15 +409,15 @@ fn explore(plr: &mut Player) {
 
     prompt!("Press `enter` to continue ");
 
-    let chest_found = math::bool_from_chance(0.1);
+    let chest_found = math::bool_from_chance(&mut plr.rng, 0.1);
     if chest_found {
-        chest_logic(plr)
+        chest_logic(plr, slot)
     } else {
-        entity_logic(plr)
+        entity_logic(plr, slot)
     }
 }
 
-fn chest_logic(plr: &mut Player) {
+fn chest_logic(plr: &mut Player, slot: Option<&str>) {
     clear_terminal();
 
     let cur_place = &plr.cur_place;
@@ -188,23 +430,65 @@ fn chest_logic(plr: &mut Player) {
     let reward_pool = cur_place.get_drops();
     prompt!("Press `enter` to open\n");
     plr.fetch_drop(reward_pool, "You found a(n)");
+    autosave(plr, slot);
     prompt!("Press `enter` to continue ");
 }
 
-fn entity_logic(plr: &mut Player) {
-    let places_entities = plr.cur_place.get_entities();
-    let Some(mut chosen_entity) = math::weigh_vec(places_entities) else {
+fn entity_logic(plr: &mut Player, slot: Option<&str>) {
+    let group = plr.cur_place.roll_group(&mut plr.rng, plr.level);
+    if group.is_empty() {
         return eprintln!("Failed to fetch an entity");
+    }
+    entities::encounter(group, plr);
+    autosave(plr, slot);
+}
+
+fn challenge_boss(plr: &mut Player, slot: Option<&str>) {
+    clear_terminal();
+    let place = plr.cur_place.clone();
+
+    let Some(boss) = place.get_boss() else {
+        return drop(prompt!(
+            "Nothing in the {} is worth challenging. Press `enter` to continue ",
+            place.name.to_lowercase()
+        ));
     };
-    chosen_entity.encounter(plr);
+    if plr.has_beaten_boss(place.location()) {
+        return drop(prompt!(
+            "You've already defeated the {}. Press `enter` to continue ",
+            boss.name
+        ));
+    }
+
+    show_header(&format!("The {} awaits...", boss.name));
+    show_sprite(entities::sprite_path(&boss.name, &place.name));
+    loop {
+        let inp = prompt!(
+            "There's no running from this fight. Challenge the {}? Enter (y/n): ",
+            boss.name
+        );
+        match inp.to_lowercase().as_str() {
+            "y" => break,
+            "n" => return,
+            _ => warn!("Invalid input"),
+        }
+    }
+
+    // Surviving isn't enough, e.g., a boss that flees keeps its key
+    let group = entities::encounter(vec![boss], plr);
+    if !group[0].is_alive() {
+        plr.defeat_boss();
+        prompt!("Press `enter` to continue ");
+    }
+    autosave(plr, slot);
 }
 
-fn travel(plr: &mut Player) {
+fn travel(plr: &mut Player, slot: Option<&str>) {
     clear_terminal();
 
-    println!("{}Travel Options: {}\n", color("Blue"), color("Reset"));
+    show!("{}Travel Options: {}\n", color("Blue"), color("Reset"));
     let locations = Place::get_registered();
-    let travel_opts = travel_opts(&locations);
+    let travel_opts = travel_opts(&locations, plr);
     let len = locations.len();
     list_items(travel_opts); // ...Also dump the Vec
 
@@ -220,11 +504,13 @@ fn travel(plr: &mut Player) {
         let Some(ind) = indexize(&inp, len) else {
             continue;
         };
-        let loc = locations[ind]; // Indexize assures this
-        let key_name = format!("{} Key", loc);
+        let loc = &locations[ind]; // Indexize assures this
 
-        if !loc.req_key() || plr.has_item(&key_name) {
-            plr.cur_place = Place::new(loc);
+        if plr.can_travel_to(loc)
+            && let Some(place) = Place::new(loc.clone())
+        {
+            plr.cur_place = place;
+            autosave(plr, slot);
             prompt!(
                 "You're now at the {}. Press `enter` to continue ",
                 loc.to_string().to_lowercase()
@@ -236,19 +522,24 @@ fn travel(plr: &mut Player) {
     }
 }
 
-fn travel_opts(locs: &[Loc]) -> Vec<String> {
-    locs.into_iter()
-        .map(|loc| {
-            if loc.req_key() {
+fn travel_opts(locs: &[Loc], plr: &Player) -> Vec<String> {
+    locs.iter()
+        .map(|loc| match loc.req_key() {
+            Some(_) if plr.can_travel_to(loc) => loc.to_string(),
+            Some(key) => {
+                // e.g., "(Requires `Cave Key`, beat the Mountains boss)"
+                let hint = loc
+                    .guarded_by()
+                    .map_or(String::new(), |by| format!(", beat the {by} boss"));
                 format!(
-                    "{}{} (Requires `{0} Key`){}",
+                    "{}{} (Requires `{}`{hint}){}",
                     loc,
                     color("Green"),
+                    key,
                     color("Reset")
                 )
-            } else {
-                loc.to_string()
             }
+            None => loc.to_string(),
         })
         .collect()
 }
@@ -259,10 +550,10 @@ fn meditate(plr: &mut Player) {
     show_sprite(String::from("misc/buddha.ans"));
 
     if !plr.has_item("Tranquility Stone") {
-        thread::sleep(Duration::from_millis(2_500));
+        frontend::pause(Duration::from_millis(2_500));
     }
 
-    let heal_amount = math::rng_from_range::<u16>((20, 60));
+    let heal_amount = math::rng_from_range::<u16>(&mut plr.rng, (20, 60));
     let actual = plr.heal(heal_amount);
 
     if plr.is_full_hp() {
@@ -275,16 +566,25 @@ fn meditate(plr: &mut Player) {
         );
     }
     plr.display_health();
+
+    let restored = plr.restore_stamina(plr.max_stamina);
+    if restored > 0 {
+        inform!(
+            "\nYour stamina is restored {}(+{restored} SP){}",
+            color("Cyan"),
+            color("Blue")
+        );
+    }
     prompt!("Press `enter` to continue ");
 }
 
-pub fn view_inventory(plr: &mut Player) {
+fn view_inventory(plr: &mut Player) {
     loop {
         clear_terminal();
         display_inv_items(&plr.inventory);
 
         if plr.inventory.is_empty() {
-            println!("Inventory is empty 🫙");
+            show!("Inventory is empty 🫙");
             show_equipped(plr);
             return drop(prompt!("\nPress `enter` to exit "));
         }
@@ -309,7 +609,7 @@ fn show_equipped(plr: &Player) {
         _ => "None equipped",
     };
 
-    println!(
+    show!(
         "\n{} {}\n{} {}",
         GREEN("Equipped Weapon:"),
         plr.weapon.name,
@@ -319,15 +619,15 @@ fn show_equipped(plr: &Player) {
 }
 
 fn display_inv_items(inventory: &Inventory) {
-    println!(
+    show!(
         "{}Here's your inventory: {}\n",
         color("Blue"),
         color("Reset")
     );
 
     let proc: Vec<_> = inventory
-        .into_iter()
-        .map(|(itm, qty)| format!("{} (x{})", itm.name, qty))
+        .iter()
+        .map(|(itm, qty)| format!("{} (x{})", itm.painted_name(), qty))
         .collect();
 
     list_items(&proc);
@@ -349,21 +649,28 @@ fn manage_item(plr: &mut Player, selected: usize) {
             Some(info_f("Name:", &itm.name)),
             Some(info_f("Quantity:", qty)),
             Some(info_f("Description:", &itm.desc)),
-            Some(info_f("Class:", &itm_type)),
+            Some(info_f("Class:", itm_type)),
+            Some(info_f("Rarity:", itm.rarity.paint(itm.rarity))),
             match itm_type {
                 IType::Weapon { damage } => Some(info_f("Damage:", damage)),
                 IType::Healer { amount } => Some(info_f("Heals:", amount)),
-                IType::Armor { reduction } => Some(info_f(
+                IType::Armor { reduction, .. } => Some(info_f(
                     "Damage Reduction:",
                     format!("{:.1}%", reduction * 100.),
                 )),
                 _ => None,
             },
+            match itm_type {
+                IType::Armor { resists, .. } if !resists.is_none() => {
+                    Some(info_f("Resistances:", resists))
+                }
+                _ => None,
+            },
         ];
 
         inform!("\nItem Information:\n");
         for field in item_info.into_iter().flatten() {
-            println!("{field}")
+            show!("{field}")
         }
 
         let options = match itm_type {
@@ -391,7 +698,12 @@ fn manage_item(plr: &mut Player, selected: usize) {
         };
 
         match options.get(chosen) {
-            Some(&"Use") => itm.use_item(plr, selected),
+            Some(&"Use") => {
+                let cured = itm.use_item(plr, selected);
+                if cured {
+                    prompt!("Your ailments fade. Press `enter` to continue ");
+                }
+            }
             Some(&"Equip") => plr.equip_from_inventory(selected),
             Some(&"Delete") => loop {
                 warn!("\nAre you sure you want to delete this item?");
@@ -407,7 +719,33 @@ fn manage_item(plr: &mut Player, selected: usize) {
     }
 }
 
-fn view_stats(plr: &Player) {
+// Quietly keeps the slot's autosave current; a dead player isn't worth resuming
+fn autosave(plr: &Player, slot: Option<&str>) {
+    let Some(slot) = slot else {
+        return;
+    };
+    if !plr.is_alive() {
+        return;
+    }
+    if let Err(er) = save::autosave(slot, plr) {
+        warn!("Autosave failed: {er}");
+    }
+}
+
+fn save_game(plr: &Player, slot: Option<&str>) {
+    clear_terminal();
+    let Some(slot) = slot else {
+        warn!("There's nothing to save to while watching a replay");
+        return drop(prompt!("Press `enter` to continue "));
+    };
+    match save::save(slot, plr) {
+        Ok(()) => inform!("Your progress was saved to `{slot}`"),
+        Err(er) => warn!("{er}"),
+    }
+    prompt!("Press `enter` to continue ");
+}
+
+fn view_stats(plr: &mut Player) {
     let Player {
         level,
         xp_multiplier,
@@ -415,16 +753,181 @@ fn view_stats(plr: &Player) {
     } = *plr;
     clear_terminal();
     inform!("Here are your stats:");
+    if let Some(class) = plr.class {
+        show!("{} {class}", GREEN("Class:"));
+    }
 
     inform!("\n\n--- Health: ---\n");
     plr.display_health();
+    inform!("\n\n--- Combat: ---\n");
+    show!(
+        "{} {}/{} SP",
+        GREEN("Stamina:"),
+        plr.cur_stamina,
+        plr.max_stamina
+    );
+    show!(
+        "{} {} ({} weapon + {} base + {} strength)",
+        GREEN("Attack:"),
+        plr.attack_damage(),
+        plr.weapon_damage(),
+        plr.base_attack,
+        plr.attributes.attack_bonus()
+    );
+    let skills: Vec<String> = Skill::unlocked(level)
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let next = Skill::get_registered()
+        .into_iter()
+        .find(|skill| skill.unlock_level() > level)
+        .map_or(String::new(), |skill| {
+            format!(" (next: {skill} at level {})", skill.unlock_level())
+        });
+    if skills.is_empty() {
+        show!("{} None yet{next}", GREEN("Skills:"));
+    } else {
+        show!("{} {}{next}", GREEN("Skills:"), skills.join(", "));
+    }
+    inform!("\n\n--- Attributes: ---\n");
+    for attr in Attribute::get_registered() {
+        show!(
+            "{} {} ({})",
+            GREEN(&format!("{attr}:")),
+            plr.attributes.get(attr),
+            plr.attributes.describe(attr)
+        );
+    }
     inform!("\n\n--- Leveling: ---\n");
-    println!(
+    show!(
         "{} {level}\n{} x{xp_multiplier:.2}",
         GREEN("Current Level:"),
         GREEN("XP Multiplier:")
     );
     plr.display_leveling();
+    // Passing this to `--seed` reproduces the session's rolls
+    show!("{} {}\n", GREEN("Seed:"), plr.rng.seed());
+
+    if plr.attribute_points == 0 {
+        prompt!("Press `enter` to exit ");
+        return;
+    }
+    let inp = prompt!(
+        "You have {} unspent attribute point(s). Enter `a` to allocate them, or press `enter` to exit: ",
+        plr.attribute_points
+    );
+    if inp.eq_ignore_ascii_case("a") {
+        allocate_points(plr);
+    }
+}
+
+fn allocate_points(plr: &mut Player) {
+    let attrs = Attribute::get_registered();
+
+    while plr.attribute_points > 0 {
+        clear_terminal();
+        inform!("Unspent attribute points: {}\n", plr.attribute_points);
+
+        let labels: Vec<String> = attrs
+            .iter()
+            .map(|&attr| {
+                format!(
+                    "{attr} {} ({})",
+                    plr.attributes.get(attr),
+                    plr.attributes.describe(attr)
+                )
+            })
+            .collect();
+        let prompt = format!(
+            "Raise which attribute? Enter (1-{}) or press `enter` to stop: ",
+            attrs.len()
+        );
+        let Some(ind) = frontend::ask_choice(&prompt, &labels, true) else {
+            return;
+        };
+        plr.attributes.raise(attrs[ind]);
+        plr.attribute_points -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content;
+
+    fn shown(transcript: &[String], text: &str) -> bool {
+        transcript.iter().any(|line| line.contains(text))
+    }
 
-    prompt!("Press `enter` to exit ");
+    #[test]
+    fn a_recording_replays_the_session_exactly() {
+        let path =
+            std::env::temp_dir().join(format!("adventura-{}-replay.toml", std::process::id()));
+
+        // Explores with whatever's first on every menu until the player goes down
+        let transcript = frontend::script(&["1"; 2_000]);
+        let mut plr = Player::new(String::from("Tess"));
+        plr.rng = math::GameRng::seeded(11);
+        plr.max_health = 60;
+        plr.cur_health = 60;
+        recording::start_at(&path, &plr).unwrap_or_else(|er| panic!("{er}"));
+        play(&mut plr, None);
+        recording::stop();
+        let played = transcript.borrow().clone();
+
+        let rec = recording::load(&path).unwrap_or_else(|er| panic!("{er}"));
+        let _ = std::fs::remove_file(&path);
+        let inputs: Vec<&str> = rec.inputs.iter().map(String::as_str).collect();
+        let transcript = frontend::script(&inputs);
+        let mut player = rec.player;
+        player.rng = math::GameRng::seeded(rec.seed);
+        play(&mut player, None);
+
+        assert!(played.iter().any(|line| line.contains("You died...")));
+        assert_eq!(*transcript.borrow(), played);
+    }
+
+    #[test]
+    fn travel_is_refused_without_the_key() {
+        let transcript = frontend::script(&["2", ""]);
+        let mut plr = Player::new(String::from("Tess"));
+
+        travel(&mut plr, None);
+
+        let transcript = transcript.borrow();
+        assert!(shown(&transcript, "Mountains (Requires `Mountains Key`"));
+        assert!(shown(&transcript, "You don't have access to this place"));
+        assert_eq!(plr.cur_place.name, "Forest");
+    }
+
+    #[test]
+    fn travel_goes_through_with_the_key() {
+        let transcript = frontend::script(&["2", ""]);
+        let mut plr = Player::new(String::from("Tess"));
+        let key = content::get().item("Mountains Key").unwrap().clone();
+        plr.add_to_inventory(key, 1);
+
+        travel(&mut plr, None);
+
+        assert!(shown(&transcript.borrow(), "You're now at the mountains"));
+        assert_eq!(plr.cur_place.name, "Mountains");
+    }
+
+    #[test]
+    fn using_a_healer_from_the_inventory() {
+        // Pick the apple, use it, then leave the inventory
+        let transcript = frontend::script(&["1", "1", ""]);
+        let mut plr = Player::new(String::from("Tess"));
+        let apple = content::get().item("Apple").unwrap().clone();
+        plr.add_to_inventory(apple, 1);
+        plr.cur_health = 50;
+
+        view_inventory(&mut plr);
+
+        let transcript = transcript.borrow();
+        assert!(shown(&transcript, "[1] >> Apple (x1)"));
+        assert!(shown(&transcript, "Heals: 25"));
+        assert_eq!(plr.cur_health, 75);
+        assert!(plr.inventory.is_empty());
+    }
 }
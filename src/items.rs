@@ -1,24 +1,171 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
+use super::content;
 use super::player::Player;
 use super::utilities::*;
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
     pub desc: String,
     pub item_type: ItemType,
+    #[serde(default)]
+    pub rarity: Rarity,
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum ItemType {
-    Weapon { damage: u16 },
-    Healer { amount: u16 },
-    Armor { reduction: f64 },
+    Weapon {
+        damage: u16,
+    },
+    Healer {
+        amount: u16,
+    },
+    Armor {
+        reduction: f64,
+        #[serde(default, skip_serializing_if = "Resistances::is_none")]
+        resists: Resistances,
+    },
     Key,
-    Special { function: fn(&mut Player) }, // experimental
+    Special {
+        effect: Effect,
+    }, // experimental
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Deserialize)]
+pub enum Element {
+    Fire,
+    Frost,
+    Shock,
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fire => write!(f, "Fire"),
+            Self::Frost => write!(f, "Frost"),
+            Self::Shock => write!(f, "Shock"),
+        }
+    }
+}
+
+impl Element {
+    pub fn get_registered() -> Vec<Self> {
+        vec![Self::Fire, Self::Frost, Self::Shock]
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Common => write!(f, "Common"),
+            Self::Uncommon => write!(f, "Uncommon"),
+            Self::Rare => write!(f, "Rare"),
+            Self::Epic => write!(f, "Epic"),
+            Self::Legendary => write!(f, "Legendary"),
+        }
+    }
+}
+
+impl Rarity {
+    /// Scales an item's drop chance, see `Item::roll_drop`
+    pub fn drop_weight(&self) -> f64 {
+        match self {
+            Self::Common => 1.,
+            Self::Uncommon => 0.8,
+            Self::Rare => 0.6,
+            Self::Epic => 0.4,
+            Self::Legendary => 0.2,
+        }
+    }
+
+    /// Common items keep whatever color they're shown in
+    pub fn color(&self) -> Option<&'static str> {
+        match self {
+            Self::Common => None,
+            Self::Uncommon => Some("Green"),
+            Self::Rare => Some("Cyan"),
+            Self::Epic => Some("Magenta"),
+            Self::Legendary => Some("Yellow"),
+        }
+    }
+
+    /// `text` in this rarity's color
+    pub fn paint<T: Display>(&self, text: T) -> String {
+        match self.color() {
+            Some(clr) => paint_text(text, clr),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// Share of elemental damage an armor blocks, negative for a weakness (e.g., -0.25 = 25% more)
+#[derive(PartialEq, Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct Resistances {
+    #[serde(default)]
+    pub fire: f64,
+    #[serde(default)]
+    pub frost: f64,
+    #[serde(default)]
+    pub shock: f64,
 }
 
+impl Resistances {
+    pub fn get(&self, element: Element) -> f64 {
+        match element {
+            Element::Fire => self.fire,
+            Element::Frost => self.frost,
+            Element::Shock => self.shock,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// e.g., "Fire +30%, Shock -25%"
+impl Display for Resistances {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let listed: Vec<String> = Element::get_registered()
+            .into_iter()
+            .filter(|&element| self.get(element) != 0.)
+            .map(|element| format!("{element} {:+.0}%", self.get(element) * 100.))
+            .collect();
+        write!(f, "{}", listed.join(", "))
+    }
+}
+
+/// Stable identifier for a special item's effect, so it survives a save file
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Effect {
+    MagicTea,
+    XpPotion,
+}
+
+impl Effect {
+    pub fn apply(&self, plr: &mut Player) {
+        match self {
+            Self::MagicTea => {
+                plr.max_health += 15;
+                plr.heal(15);
+            }
+            Self::XpPotion => plr.xp_multiplier += 0.1,
+        }
+    }
+}
 
 impl Display for ItemType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -32,7 +179,7 @@ impl Display for ItemType {
     }
 }
 
-pub type Drops = Vec<(Item, f64, bool)>; // Item, Chance (e.g., 0.01 = 1%), Duplicates allowed
+pub type Drops = Vec<(Item, f64, bool)>; // Item, Chance (a weight, see `DropDef`), Duplicates allowed
 
 impl Item {
     pub fn new(name: &str, desc: &'static str, item_type: ItemType) -> Self {
@@ -40,76 +187,60 @@ impl Item {
             name: name.to_owned(),
             desc: desc.to_owned(),
             item_type,
+            rarity: Rarity::Common,
         }
     }
 
-    pub fn use_item(&self, plr: &mut Player, loc: usize) {
-        match self.item_type {
-            ItemType::Healer { amount } => drop(plr.heal(amount)),
-            ItemType::Special { function } => function(plr),
-            except => return eprintln!("Attempted to use a `{}` item", except),
+    /// Its name in its rarity's color
+    pub fn painted_name(&self) -> String {
+        self.rarity.paint(&self.name)
+    }
+
+    /// Whether `use_item` does anything with it
+    pub fn is_usable(&self) -> bool {
+        matches!(
+            self.item_type,
+            ItemType::Healer { .. } | ItemType::Special { .. }
+        )
+    }
+
+    /// Returns whether it cured any ailments, see `Statuses::cure`
+    pub fn use_item(&self, plr: &mut Player, loc: usize) -> bool {
+        let cured = match self.item_type {
+            ItemType::Healer { amount } => {
+                plr.heal(amount);
+                plr.statuses.cure()
+            }
+            ItemType::Special { effect } => {
+                effect.apply(plr);
+                false
+            }
+            except => {
+                eprintln!("Attempted to use a `{}` item", except);
+                return false;
+            }
         };
         plr.remove_from_inventory(loc, 1);
+        cured
     }
 
-    /// Odds add up to .60 (60%) currently
+    /// Dropped everywhere, see `base_drops` in the content directory's `items.toml`
     pub fn get_base_drops() -> Drops {
-        vec![
-            // total c = 60
-            (
-                Item::new("Apple", "Crunchy :3", ItemType::Healer { amount: 25 }),
-                0.39,
-                true,
-            ),
-            (
-                Item::new(
-                    "Fairy Milk Bottle",
-                    "The source? You're asking too many questions...",
-                    ItemType::Healer { amount: 40 },
-                ),
-                0.10,
-                true,
-            ),
-            (
-                Item::new(
-                    "Magic Tea",
-                    "Increases max HP +15 and heals by the same amount",
-                    ItemType::Special {
-                        function: |plr| {
-                            plr.max_health += 15;
-                            plr.heal(15);
-                        },
-                    },
-                ),
-                0.05,
-                true,
-            ),
-            (
-                Item::new(
-                    "XP Potion",
-                    "Increases XP gain +10%",
-                    ItemType::Special {
-                        function: |plr| plr.xp_multiplier += 0.1,
-                    },
-                ),
-                0.05,
-                true,
-            ),
-            (
-                Item::new("Tranquility Stone", "Meditate instantly", ItemType::Key),
-                0.01,
-                false,
-            ),
-        ]
-    }
-
-    pub fn roll_drop(drops: Drops, plr: &Player) -> Option<Item> {
+        let content = content::get();
+        content.drops(&content.base_drops)
+    }
+
+    pub fn roll_drop(drops: Drops, plr: &mut Player) -> Option<Item> {
+        let luck = plr.attributes.drop_luck();
         let proc: Vec<_> = drops
             .into_iter()
             .filter(|(itm, _, can_dupe)| *can_dupe || !plr.has_item(&itm.name))
-            .map(|(i, c, _)| (i, c))
+            .map(|(i, c, _)| {
+                let weight = c.powf(luck) * i.rarity.drop_weight();
+                (i, weight)
+            })
             .collect();
 
-        math::weigh_vec(proc)
+        math::weigh_vec(&mut plr.rng, proc)
     }
 }
@@ -1,9 +1,19 @@
 #[macro_use]
 extern crate input_macro;
 
+pub mod attributes;
+pub mod check;
+pub mod classes;
+pub mod content;
 pub mod entities;
+pub mod frontend;
 pub mod game;
 pub mod items;
 pub mod places;
 pub mod player;
+pub mod recording;
+pub mod save;
+pub mod skills;
+pub mod status;
+pub mod tools;
 pub mod utilities;
@@ -1,9 +1,130 @@
-use adventura::game;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use adventura::tools::simulate::{self, Loadout};
+use adventura::tools::xp_curve::{self, Tuning};
+use adventura::{check, content, game, recording};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `check-content [dir]` lints a content pack instead of playing
+    if let Some("check-content") = args.first().map(String::as_str) {
+        let dir = args.get(1).map_or(content::CONTENT_DIR, String::as_str);
+        let clean = check::check_content(Path::new(dir));
+        return if clean {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+
+    let seed = match parse_flag::<u64>(&args, "--seed") {
+        Ok(seed) => seed,
+        Err(er) => return fail(&er),
+    };
+
+    if let Err(er) = content::init() {
+        return fail(&format!("Loading content failed: {er}"));
+    }
+
+    let tool = match args.first().map(String::as_str) {
+        Some("simulate") => Some(run_simulation(&args, seed)),
+        Some("xp-curve") => Some(run_xp_curve(&args)),
+        _ => None,
+    };
+    if let Some(result) = tool {
+        return match result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(er) => fail(&er),
+        };
+    }
+
+    // `--replay <file>` watches a recorded session before playing as usual
+    if let Some(path) = flag_value(&args, "--replay") {
+        match recording::load(Path::new(path)) {
+            Ok(rec) => game::run_recording(rec),
+            Err(er) => return fail(&format!("Loading the replay failed: {er}")),
+        }
+    } else {
+        game::run_game(seed);
+    }
 
-fn main() {
-    game::run_game();
     while game::replay() {
-        game::run_game();
+        game::run_game(seed);
     }
     println!("Program terminated");
+    ExitCode::SUCCESS
+}
+
+/// `simulate [--class name] [--weapon <damage|name>] [--armor <reduction|name>] [--level n] [--hp n] [--fights n]`
+fn run_simulation(args: &[String], seed: Option<u64>) -> Result<(), String> {
+    let mut loadout = Loadout::default();
+
+    if let Some(class) = flag_value(args, "--class") {
+        loadout.class = Some(simulate::class_from_arg(class)?);
+    }
+    if let Some(weapon) = flag_value(args, "--weapon") {
+        loadout.weapon = Some(simulate::weapon_from_arg(weapon)?);
+    }
+    if let Some(armor) = flag_value(args, "--armor") {
+        loadout.armor = Some(simulate::armor_from_arg(armor)?);
+    }
+    if let Some(level) = parse_flag(args, "--level")? {
+        loadout.level = level;
+    }
+    if let Some(hp) = parse_flag(args, "--hp")? {
+        loadout.max_health = Some(hp);
+    }
+    let fights = parse_flag(args, "--fights")?.unwrap_or(1_000);
+
+    simulate::simulate(&loadout, fights, seed.unwrap_or_else(rand::random));
+    Ok(())
+}
+
+/// `xp-curve [--levels n] [--a x] [--b x] [--c x] [--omega x] [--rho x] [--xi x]`,
+/// any of the constants sets up an alternate curve to compare against
+fn run_xp_curve(args: &[String]) -> Result<(), String> {
+    let mut alt = Tuning::default();
+    let mut changed = false;
+
+    let constants = [
+        ("--a", &mut alt.curve.a),
+        ("--b", &mut alt.curve.b),
+        ("--c", &mut alt.curve.c),
+        ("--omega", &mut alt.gain.omega),
+        ("--rho", &mut alt.gain.rho),
+        ("--xi", &mut alt.gain.xi),
+    ];
+    for (flag, constant) in constants {
+        if let Some(val) = parse_flag(args, flag)? {
+            *constant = val;
+            changed = true;
+        }
+    }
+    let levels = parse_flag(args, "--levels")?.unwrap_or(20);
+
+    xp_curve::xp_curve(levels, changed.then_some(alt));
+    Ok(())
+}
+
+fn fail(msg: &str) -> ExitCode {
+    eprintln!("{msg}");
+    ExitCode::FAILURE
+}
+
+/// e.g., `--seed 42` -> Some("42"), a trailing `--seed` -> Some("")
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let pos = args.iter().position(|arg| arg == flag)?;
+    Some(args.get(pos + 1).map_or("", String::as_str))
+}
+
+fn parse_flag<T: FromStr>(args: &[String], flag: &str) -> Result<Option<T>, String> {
+    flag_value(args, flag)
+        .map(|val| {
+            val.parse()
+                .map_err(|_| format!("`{flag}` expects a number, got `{val}`"))
+        })
+        .transpose()
 }
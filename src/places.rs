@@ -1,161 +1,147 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
+use super::content::{self, PlaceDef};
 use super::entities::Entity;
-use super::items::ItemType as IType;
 use super::items::*;
+use super::utilities::math::{self, GameRng};
 
+// Saved as its `Location`; the rest is rebuilt from content on load
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "Location", into = "Location")]
 pub struct Place {
     pub name: String,
     location: Location,
     pub desc: String,
 }
 
-#[derive(PartialEq, Clone, Copy)]
-pub enum Location {
-    Forest,
-    Mountains,
-    Cave,
-}
+/// Names one of the places in the content directory
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Location(String);
 
 impl Location {
-    pub fn req_key(&self) -> bool {
-        !matches!(self, Self::Forest /* | Self::... */)
+    /// Name of the item needed to travel here, if any, see `Player::can_travel_to`
+    pub fn req_key(&self) -> Option<&'static str> {
+        content::get().place(self)?.key.as_deref()
+    }
+
+    /// The place whose boss hands over the key to this one
+    pub fn guarded_by(&self) -> Option<Location> {
+        let key = self.req_key()?;
+        content::get()
+            .places
+            .iter()
+            .find(|place| {
+                let boss = place.boss.as_ref();
+                boss.is_some_and(|boss| boss.key.as_deref() == Some(key))
+            })
+            .map(|place| Location(place.name.clone()))
     }
 }
 
 // Really, really, useful
 impl Display for Location {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Forest => write!(f, "Forest"),
-            Self::Cave => write!(f, "Cave"),
-            Self::Mountains => write!(f, "Mountains"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
-fn new_ent(n: &'static str, h: u16, d: (u16, u16)) -> Entity {
-    Entity::new(n, h, d)
+impl TryFrom<Location> for Place {
+    type Error = String;
+
+    fn try_from(location: Location) -> Result<Self, Self::Error> {
+        let err = format!("`{location}` isn't a registered place");
+        Self::new(location).ok_or(err)
+    }
 }
 
-fn new_drop(n: &'static str, d: &'static str, i: ItemType) -> Item {
-    Item::new(n, d, i)
+impl From<Place> for Location {
+    fn from(place: Place) -> Self {
+        place.location
+    }
 }
 
 impl Place {
-    pub fn new(location: Location) -> Self {
-        use Location as Loc;
-        match location {
-            Loc::Forest => Self::build(location, "Ooh mystical"),
-            Loc::Mountains => Self::build(location, "The goats bite..."),
-            Loc::Cave => Self::build(location, "Spooky"),
-        }
+    pub fn new(location: Location) -> Option<Self> {
+        content::get().place(&location).map(Self::build)
+    }
+
+    /// Where new characters begin, i.e., the first registered place
+    pub fn starting() -> Self {
+        Self::build(&content::get().places[0]) // Loading assures there's one
     }
 
-    fn build(location: Location, desc: &'static str) -> Self {
+    fn build(def: &PlaceDef) -> Self {
         Self {
-            name: location.to_string(),
-            location,
-            desc: desc.to_string(),
+            name: def.name.clone(),
+            location: Location(def.name.clone()),
+            desc: def.desc.clone(),
         }
     }
 
+    fn def(&self) -> &'static PlaceDef {
+        content::get()
+            .place(&self.location)
+            .expect("Places are only built from loaded content")
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
     pub fn get_registered() -> Vec<Location> {
-        vec![Location::Forest, Location::Mountains, Location::Cave]
+        content::get()
+            .places
+            .iter()
+            .map(|p| Location(p.name.clone()))
+            .collect()
     }
 
     pub fn get_entities(&self) -> Vec<(Entity, f64)> {
-        use Location as Loc;
-        match self.location {
-            Loc::Forest => vec![
-                // Name, Max HP, Attack Range (Min, Max), Encounter % (e.g., 0.01 = 1%)
-                (new_ent("Goblin", 75, (5, 15)), 0.5),
-                (new_ent("Elf", 100, (10, 20)), 0.45),
-                (new_ent("Mud Wizard", 120, (20, 45)), 0.05),
-            ],
-            Loc::Mountains => vec![
-                (new_ent("Goat", 95, (15, 25)), 0.5),
-                (new_ent("Snowman", 110, (20, 35)), 0.45),
-                (new_ent("Yeti", 175, (30, 55)), 0.05),
-            ],
-            Loc::Cave => vec![
-                (new_ent("Spider", 110, (20, 30)), 0.5),
-                (new_ent("Dweller", 125, (25, 45)), 0.45),
-                (new_ent("Stalactite Golem", 200, (40, 65)), 0.05),
-            ],
+        self.def()
+            .entities
+            .iter()
+            .map(|ent| (Entity::from_def(ent), ent.weight))
+            .collect()
+    }
+
+    /// Who the player runs into, an entity and whichever companions it brought along
+    pub fn roll_group(&self, rng: &mut GameRng, level: u16) -> Vec<Entity> {
+        let weighted = self.def().entities.iter().map(|ent| (ent, ent.weight));
+        let Some(leader) = math::weigh_vec(rng, weighted.collect()) else {
+            return Vec::new();
+        };
+
+        let mut group = vec![Entity::from_def(leader).scaled(level).promoted(rng)];
+        for companion in &leader.companions {
+            let count = math::rng_from_range(rng, companion.count);
+            for _ in 0..count {
+                group.extend(self.spawn(&companion.name, rng, level));
+            }
         }
+        group
+    }
+
+    pub fn get_boss(&self) -> Option<Entity> {
+        self.def().boss.as_ref().map(Entity::from_boss)
+    }
+
+    pub fn boss_key(&self) -> Option<&'static str> {
+        self.def().boss.as_ref()?.key.as_deref()
+    }
+
+    /// Grown to the player's `level`, and now and then an elite
+    pub fn spawn(&self, name: &str, rng: &mut GameRng, level: u16) -> Option<Entity> {
+        let def = self.def().entities.iter().find(|ent| ent.name == name)?;
+        Some(Entity::from_def(def).scaled(level).promoted(rng))
     }
 
     /// Includes base drops
     pub fn get_drops(&self) -> Drops {
-        use Location as Loc;
-        // Base items
         let mut list = Item::get_base_drops();
-        let exclusives = match self.location {
-            Loc::Forest => vec![
-                (
-                    new_drop(
-                        "Wooden Sword",
-                        "Give your enemies splinters",
-                        IType::Weapon { damage: 35 },
-                    ),
-                    0.25,
-                    false,
-                ),
-                (
-                    new_drop(
-                        "Wooden Armor",
-                        "May occasionally give you splinters",
-                        ItemType::Armor { reduction: 0.25 },
-                    ),
-                    0.1,
-                    false,
-                ),
-                (
-                    new_drop("Mountains Key", "Unlocks the mountains", IType::Key),
-                    0.05,
-                    false,
-                ),
-            ],
-            Loc::Mountains => vec![
-                (
-                    new_drop("Iron Sword", "Very pointy", IType::Weapon { damage: 50 }),
-                    0.25,
-                    false,
-                ),
-                (
-                    new_drop(
-                        "Iron Armor",
-                        "Tough stuff",
-                        ItemType::Armor { reduction: 0.40 },
-                    ),
-                    0.1,
-                    false,
-                ),
-                (
-                    new_drop("Cave Key", "Unlocks the cave", IType::Key),
-                    0.05,
-                    false,
-                ),
-            ],
-            Loc::Cave => vec![
-                (
-                    new_drop("Blessed Sword", "Hallelujah", IType::Weapon { damage: 60 }),
-                    0.25,
-                    false,
-                ),
-                (
-                    new_drop(
-                        "Blessed Armor",
-                        "Legends say an angel kissed this",
-                        ItemType::Armor { reduction: 0.55 },
-                    ),
-                    0.15,
-                    false,
-                ),
-            ],
-        };
-        list.extend(exclusives);
+        list.extend(content::get().drops(&self.def().drops));
         list
     }
 }
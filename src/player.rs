@@ -1,16 +1,30 @@
+use serde::{Deserialize, Serialize};
+
 use crate::inform;
+use crate::show;
 use crate::warn;
 
+use super::attributes::Attributes;
+use super::classes::Class;
+use super::content::{self, Growth};
 use super::entities::Entity;
 use super::items::*;
-use super::places::Place;
+use super::places::{Location, Place};
+use super::skills::Skill;
+use super::status::Statuses;
+use super::utilities::math::HitOutcome;
 use super::utilities::*;
 
+const HIT_CHANCE: f64 = 0.95;
+
 // Testing
 pub type Inventory = Vec<(Item, u16)>; // Item and quantity
 
+#[derive(Serialize, Deserialize)]
 pub struct Player {
     pub name: String,
+    #[serde(default)]
+    pub class: Option<Class>, // None for characters from before classes
     pub cur_place: Place,
     pub max_health: u16,
     pub cur_health: u16,
@@ -18,8 +32,24 @@ pub struct Player {
     pub xp: f64,
     pub xp_multiplier: f64,
     pub level: u16,
+    #[serde(default)]
+    pub base_attack: u16, // Added to the weapon's damage, grows with level
+    #[serde(default)]
+    pub attributes: Attributes,
+    #[serde(default)]
+    pub attribute_points: u16, // Unspent
+    #[serde(default = "Player::base_stamina")]
+    pub max_stamina: u16,
+    #[serde(default = "Player::base_stamina")]
+    pub cur_stamina: u16, // Spent on skills, restored by meditating
     pub inventory: Inventory,
     pub weapon: Item,
+    #[serde(default)]
+    pub bosses_defeated: Vec<Location>, // By the place they guard
+    #[serde(skip)]
+    pub statuses: Statuses, // Only last the fight
+    #[serde(skip)]
+    pub rng: math::GameRng, // Not saved, seeded fresh each session
 }
 
 impl Player {
@@ -27,28 +57,74 @@ impl Player {
         let starter_weapon: Item = Item::new("Fists", "Punchy", ItemType::Weapon { damage: 25 });
         Player {
             name,
-            cur_place: Place::new(crate::places::Location::Forest),
+            class: None,
+            cur_place: Place::starting(),
             max_health: 100,
             cur_health: 100,
             armor: None,
             xp: 0.,
             xp_multiplier: 1.,
             level: 1,
+            base_attack: 0,
+            attributes: Attributes::default(),
+            attribute_points: 0,
+            max_stamina: Self::base_stamina(),
+            cur_stamina: Self::base_stamina(),
             inventory: Vec::new(),
             weapon: starter_weapon,
+            bosses_defeated: Vec::new(),
+            statuses: Statuses::default(),
+            rng: math::GameRng::default(),
+        }
+    }
+
+    fn base_stamina() -> u16 {
+        50
+    }
+
+    pub fn with_class(name: String, class: Class) -> Self {
+        let mut plr = Self::new(name);
+        if let Some(weapon) = content::get().item(class.starting_weapon()) {
+            plr.weapon = weapon.clone();
         }
+        plr.max_health = class.max_health();
+        plr.cur_health = plr.max_health;
+        plr.attributes = class.attributes();
+        plr.class = Some(class);
+        plr
     }
-    pub fn take_damage(&mut self, amount: u16, penetrating: bool) {
+
+    /// Returns the damage actually taken
+    pub fn take_damage(&mut self, amount: u16, penetrating: bool) -> u16 {
         let proc_damage = match &self.armor {
             Some(arm) if !penetrating => {
-                let ItemType::Armor { reduction } = arm.item_type else {
-                    return eprintln!("Equipped 'armor' isn't of type `IType::Armor`");
+                let ItemType::Armor { reduction, .. } = arm.item_type else {
+                    eprintln!("Equipped 'armor' isn't of type `IType::Armor`");
+                    return 0;
                 };
                 (amount as f64 * (1. - reduction)).round() as u16
             }
             _ => amount,
         };
-        self.cur_health -= proc_damage.min(self.cur_health);
+        self.absorb(proc_damage)
+    }
+
+    /// Gets past armor's reduction, only its resistances help. Returns the damage actually taken
+    pub fn take_elemental_damage(&mut self, amount: u16, element: Element) -> u16 {
+        let resist = match self.armor.as_ref().map(|arm| arm.item_type) {
+            Some(ItemType::Armor { resists, .. }) => resists.get(element),
+            _ => 0.,
+        };
+        self.absorb((amount as f64 * (1. - resist)).round() as u16)
+    }
+
+    // Whatever vitality and statuses don't soak up
+    fn absorb(&mut self, amount: u16) -> u16 {
+        let multiplier =
+            (1. - self.attributes.mitigation()) * self.statuses.damage_taken_multiplier();
+        let proc_damage = ((amount as f64 * multiplier).round() as u16).min(self.cur_health);
+        self.cur_health -= proc_damage;
+        proc_damage
     }
 
     pub fn heal(&mut self, amount: u16) -> u16 {
@@ -57,11 +133,44 @@ impl Player {
         proc // Return amount healed for display
     }
 
-    pub fn attack(&self, entity: &mut Entity) {
-        if let ItemType::Weapon { damage, .. } = self.weapon.item_type {
-            entity.cur_health -= damage.min(entity.cur_health);
-        } else {
+    pub fn restore_stamina(&mut self, amount: u16) -> u16 {
+        let proc = amount.min(self.max_stamina - self.cur_stamina);
+        self.cur_stamina += proc;
+        proc
+    }
+
+    /// An attack with its damage scaled by `multiplier`. Returns how it landed and the damage dealt
+    pub fn strike(&mut self, entity: &mut Entity, multiplier: f64) -> (HitOutcome, u16) {
+        let ItemType::Weapon { .. } = self.weapon.item_type else {
             eprintln!("Player doesn't have a weapon equipped... Negligence");
+            return (HitOutcome::Miss, 0);
+        };
+        let outcome = math::roll_hit(
+            &mut self.rng,
+            HIT_CHANCE,
+            entity.dodge_chance(),
+            self.attributes.crit_chance(),
+        );
+        let multiplier = multiplier
+            * outcome.multiplier()
+            * self.statuses.damage_dealt_multiplier()
+            * entity.statuses.damage_taken_multiplier();
+        let damage = (self.attack_damage() as f64 * multiplier).round() as u16;
+        let dealt = damage.min(entity.cur_health);
+        entity.cur_health -= dealt;
+        (outcome, dealt)
+    }
+
+    pub fn attack_damage(&self) -> u16 {
+        let bonus = self.base_attack + self.attributes.attack_bonus();
+        self.weapon_damage().saturating_add(bonus)
+    }
+
+    /// What the equipped weapon deals on its own, 0 if it somehow isn't a weapon
+    pub fn weapon_damage(&self) -> u16 {
+        match self.weapon.item_type {
+            ItemType::Weapon { damage } => damage,
+            _ => 0,
         }
     }
 
@@ -73,15 +182,29 @@ impl Player {
         self.cur_health == self.max_health
     }
 
-    pub fn update_xp(&mut self, for_defeating: &Entity) {
+    pub fn update_xp(&mut self, for_defeating: &[&Entity]) {
         // (& level up)
-        let xp_gain = math::calc_xp_gain(for_defeating) * self.xp_multiplier;
+        let xp_gain: f64 = for_defeating
+            .iter()
+            .map(|ent| math::calc_xp_gain(ent))
+            .sum::<f64>()
+            * self.xp_multiplier;
         self.xp += xp_gain;
 
+        // e.g., "the Goblin, the Goblin and the Elf"
+        let names: Vec<String> = for_defeating
+            .iter()
+            .map(|ent| format!("the {}", ent.name))
+            .collect();
+        let defeated = match names.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+            None => return,
+        };
+
         let lvl_change = math::calc_level(self.xp) - self.level;
         inform!(
-            "You defeated the {} and earned {}{} XP{}!",
-            for_defeating.name,
+            "You defeated {defeated} and earned {}{} XP{}!",
             color("Cyan"),
             xp_gain as u32,
             color("Blue")
@@ -96,18 +219,57 @@ impl Player {
 
     fn raise_level(&mut self) {
         self.level += 1;
+        let Growth {
+            max_health,
+            attack,
+            attribute_points,
+        } = self.grow();
         inform!(
-            "\n{}You reached level {}!{}",
+            "\n{}You reached level {}! {}{}",
             style("Italics"),
-            paint_text(&self.level.to_string(), "Cyan"),
+            paint_text(self.level, "Cyan"),
+            paint_text(
+                format!(
+                    "(+{max_health} max HP, +{attack} attack, +{attribute_points} attribute points)"
+                ),
+                "Green"
+            ),
             style("Reset")
         );
+        for skill in Skill::get_registered() {
+            if skill.unlock_level() == self.level {
+                inform!(
+                    "You learned {}! {}",
+                    paint_text(skill, "Cyan"),
+                    skill.desc()
+                );
+            }
+        }
         // Award the player an item every 5 levels
-        if self.level % 5 == 0 {
+        if self.level.is_multiple_of(5) {
             let base_drops = Item::get_base_drops();
             self.fetch_drop(base_drops, "& were awarded a(n)");
         }
     }
+    /// Applies the stats for the level just reached, see `growth.toml`
+    fn grow(&mut self) -> Growth {
+        let growth = content::get().growth_at(self.level);
+        self.max_health += growth.max_health;
+        self.cur_health += growth.max_health;
+        self.base_attack += growth.attack;
+        self.attribute_points += growth.attribute_points;
+        growth
+    }
+
+    /// Jumps straight to `level` with the growth of every level on the way, minus the fanfare
+    pub fn grow_to(&mut self, level: u16) {
+        while self.level < level {
+            self.level += 1;
+            self.grow();
+        }
+        self.xp = self.xp.max(math::xp_needed(self.level));
+    }
+
     pub fn display_leveling(&self) {
         let Self { level, xp, .. } = *self;
         let cur_lvl_xp = math::xp_needed(level) as u16;
@@ -117,12 +279,13 @@ impl Player {
         let for_next_lvl = next_lvl_xp - cur_lvl_xp;
 
         inform!("\t \nNeeded for next level:\n");
-        println!(
-            "|{}| {} |{}| [{}]\n",
+        show!(
+            "|{}| {} |{}| [{}/{} XP]\n",
             level,
             progress_bar(cur_prog, for_next_lvl, "Cyan", 15),
             level + 1,
-            format!("{}/{} XP", xp as u16, next_lvl_xp)
+            xp as u16,
+            next_lvl_xp
         );
     }
 
@@ -135,9 +298,10 @@ impl Player {
         if cur_health <= 30 {
             warn!("!!! Critical health !!!\n")
         }
-        println!(
-            " {}\n{}",
-            format!("{}/{} HP", cur_health, max_health),
+        show!(
+            " {}/{} HP\n{}",
+            cur_health,
+            max_health,
             progress_bar(cur_health, max_health, "Green", 10)
         );
     }
@@ -147,9 +311,8 @@ impl Player {
             return eprintln!("Fetching drop failed");
         };
         inform!(
-            "{msg} {}{}{}! It can be found in your inventory",
-            color("Cyan"),
-            chosen.name,
+            "{msg} {}{}! It can be found in your inventory",
+            chosen.rarity.paint(&chosen.name),
             color("Blue")
         );
         self.add_to_inventory(chosen, 1);
@@ -194,20 +357,68 @@ impl Player {
         self.remove_from_inventory(location, 1);
     }
 
+    // Stacked by name, so copies saved before a content change still join newer ones
     pub fn add_to_inventory(&mut self, item: Item, quant: u16) {
-        if let Some((_, qty)) = self.inventory.iter_mut().find(|(i, _)| *i == item) {
+        if let Some((_, qty)) = self.inventory.iter_mut().find(|(i, _)| i.name == item.name) {
             *qty += quant;
         } else {
             self.inventory.push((item, quant));
         }
     }
 
+    /// Swaps every saved item for its current definition in content, matched by name, so
+    /// older saves pick up later changes like rarities. Items content doesn't know keep theirs
+    pub fn refresh_items(&mut self) {
+        let content = content::get();
+        let fresh = |itm: &Item| content.item(&itm.name).unwrap_or(itm).clone();
+
+        self.weapon = fresh(&self.weapon);
+        self.armor = self.armor.as_ref().map(fresh);
+        for (itm, qty) in std::mem::take(&mut self.inventory) {
+            self.add_to_inventory(fresh(&itm), qty);
+        }
+    }
+
     pub fn has_item(&self, name: &str) -> bool {
         self.inventory.iter().any(|(i, _)| i.name == name)
             || self.weapon.name == name
             || self.armor.as_ref().is_some_and(|i| i.name == name)
     }
 
+    pub fn has_beaten_boss(&self, of: &Location) -> bool {
+        self.bosses_defeated.contains(of)
+    }
+
+    /// Holding the key is enough, so is beating the boss that hands it over
+    pub fn can_travel_to(&self, loc: &Location) -> bool {
+        let Some(key) = loc.req_key() else {
+            return true;
+        };
+        self.has_item(key) || loc.guarded_by().is_some_and(|by| self.has_beaten_boss(&by))
+    }
+
+    /// Records the current place's boss as beaten and hands over its key
+    pub fn defeat_boss(&mut self) {
+        let place = self.cur_place.location().clone();
+        if !self.has_beaten_boss(&place) {
+            self.bosses_defeated.push(place);
+        }
+
+        let key = self
+            .cur_place
+            .boss_key()
+            .and_then(|key| content::get().item(key));
+        if let Some(key) = key.filter(|key| !self.has_item(&key.name)) {
+            inform!(
+                "You claimed the {}{}{}! It can be found in your inventory",
+                color("Cyan"),
+                key.name,
+                color("Blue")
+            );
+            self.add_to_inventory(key.clone(), 1);
+        }
+    }
+
     pub fn silly(&mut self) {
         let stuff = [
             // Item::new("Apple", "Crunchy :3", ItemType::Healer {amount: 25}),
@@ -216,7 +427,10 @@ impl Player {
             Item::new(
                 "Divine Armor",
                 "Hallelujah 2",
-                ItemType::Armor { reduction: 0.88 },
+                ItemType::Armor {
+                    reduction: 0.88,
+                    resists: Resistances::default(),
+                },
             ),
             Item::new("Mountains Key", "..", ItemType::Key),
             Item::new("Cave Key", "...", ItemType::Key),
@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::player::Player;
+use super::save;
+
+/// Bump whenever a change to `Player` or the menus breaks older recordings
+pub const RECORDING_VERSION: u32 = 3;
+const RECORDING_DIR: &str = "replays";
+
+// Generic for the same reason as `save::SaveFile`
+#[derive(Serialize, Deserialize)]
+struct RecordingFile<P> {
+    version: u32,
+    seed: u64,
+    player: P, // As it was when the session started
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<RecordedInput>, // Appended as `[[inputs]]` tables while playing
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordedInput {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct RecordingHeader {
+    version: u32,
+}
+
+/// A session that can be played back: where it started, and everything typed since
+pub struct Recording {
+    pub seed: u64,
+    pub player: Player,
+    pub inputs: Vec<String>,
+}
+
+pub enum RecordingError {
+    Io(std::io::Error),
+    Serialize(toml::ser::Error),
+    Parse(toml::de::Error),
+    Version { found: u32, expected: u32 },
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(er) => write!(f, "Couldn't access the replay file: `{er}`"),
+            Self::Serialize(er) => write!(f, "Couldn't write the replay file: `{er}`"),
+            Self::Parse(er) => write!(f, "The replay file is corrupted: `{er}`"),
+            Self::Version { found, expected } => write!(
+                f,
+                "The replay is from version {found}, expected version {expected}"
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(er: std::io::Error) -> Self {
+        Self::Io(er)
+    }
+}
+
+impl From<toml::ser::Error> for RecordingError {
+    fn from(er: toml::ser::Error) -> Self {
+        Self::Serialize(er)
+    }
+}
+
+impl From<toml::de::Error> for RecordingError {
+    fn from(er: toml::de::Error) -> Self {
+        Self::Parse(er)
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<File>> = const { RefCell::new(None) };
+}
+
+/// Snapshots `plr` and records every input from here on. Returns where it's written
+pub fn start(plr: &Player) -> Result<PathBuf, RecordingError> {
+    fs::create_dir_all(RECORDING_DIR)?;
+    let path = PathBuf::from(RECORDING_DIR).join(format!("session-{}.toml", save::now()));
+    start_at(&path, plr)?;
+    Ok(path)
+}
+
+/// `start`, but written to `path`
+pub fn start_at(path: &Path, plr: &Player) -> Result<(), RecordingError> {
+    let file = RecordingFile {
+        version: RECORDING_VERSION,
+        seed: plr.rng.seed(),
+        player: plr,
+        inputs: Vec::new(),
+    };
+    fs::write(path, toml::to_string(&file)?)?;
+
+    let active = OpenOptions::new().append(true).open(path)?;
+    ACTIVE.set(Some(active));
+    Ok(())
+}
+
+pub fn stop() {
+    ACTIVE.set(None);
+}
+
+/// Called by the frontend with every line the player enters. Appended right away,
+/// so a crash still leaves a usable recording
+pub fn record(input: &str) {
+    ACTIVE.with_borrow_mut(|active| {
+        let Some(active) = active else {
+            return;
+        };
+        let text = toml::Value::String(input.to_owned());
+        if let Err(er) = write!(active, "\n[[inputs]]\ntext = {text}\n") {
+            eprintln!("Recording input failed: {er}");
+        }
+    });
+}
+
+pub fn load(path: &Path) -> Result<Recording, RecordingError> {
+    let content = fs::read_to_string(path)?;
+
+    let RecordingHeader { version } = toml::from_str(&content)?;
+    if version != RECORDING_VERSION {
+        return Err(RecordingError::Version {
+            found: version,
+            expected: RECORDING_VERSION,
+        });
+    }
+
+    let file: RecordingFile<Player> = toml::from_str(&content)?;
+    Ok(Recording {
+        seed: file.seed,
+        player: file.player,
+        inputs: file.inputs.into_iter().map(|input| input.text).collect(),
+    })
+}
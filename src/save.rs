@@ -0,0 +1,322 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::places::Location;
+use super::player::Player;
+
+/// Bump whenever a change to `Player` breaks older save files
+pub const SAVE_VERSION: u32 = 1;
+const SAVE_DIR: &str = "saves";
+const AUTOSAVE_DIR: &str = "saves/autosave";
+const SAVE_EXT: &str = "toml";
+
+// Generic so saving can borrow the player while loading owns it
+#[derive(Serialize, Deserialize)]
+struct SaveFile<P> {
+    version: u32,
+    #[serde(default)]
+    last_played: u64, // Unix timestamp (seconds)
+    player: P,
+}
+
+// Just enough of the player to describe a slot without loading it
+#[derive(Deserialize)]
+struct PlayerSummary {
+    name: String,
+    level: u16,
+    cur_place: Location,
+}
+
+pub struct SaveInfo {
+    pub name: String,
+    pub level: u16,
+    pub location: Location,
+    pub last_played: u64,
+}
+
+pub struct Slot {
+    pub name: String,
+    pub info: Result<SaveInfo, SaveError>,
+    /// Only present when it's more recent than the slot's manual save
+    pub autosave: Option<SaveInfo>,
+}
+
+// Read first, so a save from another version is rejected before parsing the rest
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+pub enum SaveError {
+    Io(std::io::Error),
+    Serialize(toml::ser::Error),
+    Parse(toml::de::Error),
+    Version { found: u32, expected: u32 },
+    SlotName(String),
+    SlotTaken(String),
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(er) => write!(f, "Couldn't access the save file: `{er}`"),
+            Self::Serialize(er) => write!(f, "Couldn't write the save file: `{er}`"),
+            Self::Parse(er) => write!(f, "The save file is corrupted: `{er}`"),
+            Self::Version { found, expected } => write!(
+                f,
+                "The file is from version {found}, expected version {expected}"
+            ),
+            Self::SlotName(n) => write!(
+                f,
+                "`{n}` isn't a valid slot name (letters, numbers, spaces, `-` and `_` only)"
+            ),
+            Self::SlotTaken(n) => write!(f, "A slot named `{n}` already exists"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(er: std::io::Error) -> Self {
+        Self::Io(er)
+    }
+}
+
+impl From<toml::ser::Error> for SaveError {
+    fn from(er: toml::ser::Error) -> Self {
+        Self::Serialize(er)
+    }
+}
+
+impl From<toml::de::Error> for SaveError {
+    fn from(er: toml::de::Error) -> Self {
+        Self::Parse(er)
+    }
+}
+
+fn slot_path(slot: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("{slot}.{SAVE_EXT}"))
+}
+
+fn autosave_path(slot: &str) -> PathBuf {
+    PathBuf::from(AUTOSAVE_DIR).join(format!("{slot}.{SAVE_EXT}"))
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+pub fn is_valid_slot_name(slot: &str) -> bool {
+    !slot.is_empty()
+        && slot.len() <= 32
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+}
+
+pub fn exists(slot: &str) -> bool {
+    slot_path(slot).is_file()
+}
+
+/// Every slot in the save directory, most recently played first
+pub fn slots() -> Vec<Slot> {
+    let Ok(entries) = fs::read_dir(SAVE_DIR) else {
+        return Vec::new();
+    };
+
+    let mut slots: Vec<Slot> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == SAVE_EXT))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_owned))
+        .map(|name| {
+            let info = peek(&slot_path(&name));
+            let saved_at = info.as_ref().map_or(0, |info| info.last_played);
+            let autosave = peek(&autosave_path(&name))
+                .ok()
+                .filter(|auto| auto.last_played > saved_at);
+
+            Slot {
+                name,
+                info,
+                autosave,
+            }
+        })
+        .collect();
+
+    slots.sort_by_key(|slot| {
+        let last_played = slot
+            .autosave
+            .as_ref()
+            .or(slot.info.as_ref().ok())
+            .map_or(0, |info| info.last_played);
+        (std::cmp::Reverse(last_played), slot.name.clone())
+    });
+    slots
+}
+
+fn read(path: &Path) -> Result<String, SaveError> {
+    let content = fs::read_to_string(path)?;
+
+    let SaveHeader { version } = toml::from_str(&content)?;
+    if version != SAVE_VERSION {
+        return Err(SaveError::Version {
+            found: version,
+            expected: SAVE_VERSION,
+        });
+    }
+    Ok(content)
+}
+
+fn peek(path: &Path) -> Result<SaveInfo, SaveError> {
+    let file: SaveFile<PlayerSummary> = toml::from_str(&read(path)?)?;
+    let PlayerSummary {
+        name,
+        level,
+        cur_place,
+    } = file.player;
+
+    Ok(SaveInfo {
+        name,
+        level,
+        location: cur_place,
+        last_played: file.last_played,
+    })
+}
+
+/// Writes to a temporary file first, so a crash mid-write can't corrupt the existing save
+fn write_atomic(path: &Path, plr: &Player) -> Result<(), SaveError> {
+    let file = SaveFile {
+        version: SAVE_VERSION,
+        last_played: now(),
+        player: plr,
+    };
+    let content = toml::to_string(&file)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn remove_if_present(path: &Path) -> Result<(), SaveError> {
+    match fs::remove_file(path) {
+        Err(er) if er.kind() != std::io::ErrorKind::NotFound => Err(er.into()),
+        _ => Ok(()),
+    }
+}
+
+pub fn save(slot: &str, plr: &Player) -> Result<(), SaveError> {
+    if !is_valid_slot_name(slot) {
+        return Err(SaveError::SlotName(slot.to_owned()));
+    }
+    write_atomic(&slot_path(slot), plr)?;
+    // The autosave is now behind the manual save
+    remove_if_present(&autosave_path(slot))
+}
+
+pub fn autosave(slot: &str, plr: &Player) -> Result<(), SaveError> {
+    write_atomic(&autosave_path(slot), plr)
+}
+
+pub fn load(slot: &str) -> Result<Player, SaveError> {
+    parse(&read(&slot_path(slot))?)
+}
+
+pub fn load_autosave(slot: &str) -> Result<Player, SaveError> {
+    parse(&read(&autosave_path(slot))?)
+}
+
+// Items are saved whole, so they're brought up to date with content on the way in
+fn parse(content: &str) -> Result<Player, SaveError> {
+    let file: SaveFile<Player> = toml::from_str(content)?;
+    let mut plr = file.player;
+    plr.refresh_items();
+    Ok(plr)
+}
+
+pub fn delete(slot: &str) -> Result<(), SaveError> {
+    fs::remove_file(slot_path(slot))?;
+    remove_if_present(&autosave_path(slot))
+}
+
+pub fn duplicate(from: &str, to: &str) -> Result<(), SaveError> {
+    if !is_valid_slot_name(to) {
+        return Err(SaveError::SlotName(to.to_owned()));
+    }
+    if exists(to) {
+        return Err(SaveError::SlotTaken(to.to_owned()));
+    }
+    fs::copy(slot_path(from), slot_path(to))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content;
+    use crate::frontend;
+    use crate::items::Rarity;
+
+    // Saved before items had a rarity
+    const OLD_SAVE: &str = r#"
+version = 1
+last_played = 1792308376
+
+[player]
+name = "Tess"
+class = "Warrior"
+cur_place = "Forest"
+max_health = 120
+cur_health = 120
+xp = 0.0
+xp_multiplier = 1.0
+level = 1
+base_attack = 0
+attribute_points = 0
+max_stamina = 50
+cur_stamina = 50
+inventory = [
+    [{ name = "Fairy Milk Bottle", desc = "The source? You're asking too many questions...", item_type = { Healer = { amount = 40 } } }, 2],
+    [{ name = "Mystery Meat", desc = "Long gone from the content", item_type = { Healer = { amount = 5 } } }, 1],
+]
+
+[player.attributes]
+strength = 2
+vitality = 2
+agility = 0
+luck = 0
+
+[player.weapon]
+name = "Wooden Sword"
+desc = "Give your enemies splinters"
+
+[player.weapon.item_type.Weapon]
+damage = 35
+"#;
+
+    #[test]
+    fn old_saves_pick_up_current_items() {
+        frontend::script(&[]);
+        let mut plr = parse(OLD_SAVE).unwrap_or_else(|er| panic!("{er}"));
+
+        assert_eq!(plr.weapon.rarity, Rarity::Uncommon);
+        let (milk, qty) = &plr.inventory[0];
+        assert_eq!((milk.rarity, *qty), (Rarity::Uncommon, 2));
+        // Unknown to content, kept as saved
+        assert_eq!(plr.inventory[1].0.name, "Mystery Meat");
+
+        let dropped = content::get().item("Fairy Milk Bottle").unwrap().clone();
+        plr.add_to_inventory(dropped, 1);
+        assert_eq!(plr.inventory.len(), 2);
+        assert_eq!(plr.inventory[0].1, 3);
+    }
+}
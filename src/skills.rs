@@ -0,0 +1,62 @@
+use std::fmt::Display;
+
+/// Bought with stamina in combat, see `entities::use_skill`
+#[derive(Clone, Copy, PartialEq)]
+pub enum Skill {
+    HeavyStrike,
+    Defend,
+    Drain,
+    Stun,
+}
+
+impl Display for Skill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HeavyStrike => write!(f, "Heavy Strike"),
+            Self::Defend => write!(f, "Defend"),
+            Self::Drain => write!(f, "Drain"),
+            Self::Stun => write!(f, "Stun"),
+        }
+    }
+}
+
+impl Skill {
+    pub fn get_registered() -> Vec<Self> {
+        vec![Self::HeavyStrike, Self::Defend, Self::Drain, Self::Stun]
+    }
+
+    /// Every skill a player of `level` can use
+    pub fn unlocked(level: u16) -> Vec<Self> {
+        Self::get_registered()
+            .into_iter()
+            .filter(|skill| skill.unlock_level() <= level)
+            .collect()
+    }
+
+    pub fn unlock_level(&self) -> u16 {
+        match self {
+            Self::HeavyStrike => 2,
+            Self::Defend => 3,
+            Self::Drain => 5,
+            Self::Stun => 8,
+        }
+    }
+
+    pub fn cost(&self) -> u16 {
+        match self {
+            Self::HeavyStrike => 15,
+            Self::Defend => 10,
+            Self::Drain => 20,
+            Self::Stun => 25,
+        }
+    }
+
+    pub fn desc(&self) -> &'static str {
+        match self {
+            Self::HeavyStrike => "A hit for 75% more damage",
+            Self::Defend => "Skip your attack to take half damage this turn",
+            Self::Drain => "A hit that heals you for half the damage dealt",
+            Self::Stun => "Leave the enemy unable to attack for 2 turns",
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+
+use super::utilities::math::{self, GameRng};
+
+const CHILL_SKIP_CHANCE: f64 = 0.5;
+const WEAKENED_MULTIPLIER: f64 = 0.7; // Of damage dealt
+const SHIELDED_MULTIPLIER: f64 = 0.5; // Of damage taken
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum StatusKind {
+    Poison,   // Damage each turn, never lethal
+    Bleed,    // Damage each turn
+    Burn,     // Damage each turn, stops regeneration
+    Stun,     // Loses its turns
+    Chill,    // May lose its turns
+    Regen,    // Heals each turn
+    Weakened, // Deals less damage
+    Shielded, // Takes less damage
+}
+
+impl Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Poison => write!(f, "Poisoned"),
+            Self::Bleed => write!(f, "Bleeding"),
+            Self::Burn => write!(f, "Burning"),
+            Self::Stun => write!(f, "Stunned"),
+            Self::Chill => write!(f, "Chilled"),
+            Self::Regen => write!(f, "Regenerating"),
+            Self::Weakened => write!(f, "Weakened"),
+            Self::Shielded => write!(f, "Shielded"),
+        }
+    }
+}
+
+impl StatusKind {
+    pub fn is_harmful(&self) -> bool {
+        !matches!(self, Self::Regen | Self::Shielded)
+    }
+
+    /// Cured by healing items
+    fn is_ailment(&self) -> bool {
+        matches!(self, Self::Poison | Self::Bleed | Self::Burn)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub turns: u16,   // Of the holder's own turns it still affects
+    pub potency: u16, // HP lost or healed per turn, where it applies
+    fresh: bool,      // Applied since the holder's turn started, so that turn doesn't count
+}
+
+impl Status {
+    pub fn new(kind: StatusKind, turns: u16, potency: u16) -> Self {
+        Self {
+            kind,
+            turns,
+            potency,
+            fresh: true,
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.kind, self.turns)
+    }
+}
+
+/// A status an entity may leave on whoever it hits, declared in the content directory
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Infliction {
+    pub status: StatusKind,
+    pub chance: f64, // Per hit, e.g., 0.25 = 25%
+    pub turns: u16,
+    #[serde(default)]
+    pub potency: u16,
+}
+
+impl Infliction {
+    pub fn status(&self) -> Status {
+        Status::new(self.status, self.turns, self.potency)
+    }
+}
+
+/// What happened when a turn started
+#[derive(Default)]
+pub struct Tick {
+    pub damage: u16,
+    pub healed: u16,
+    pub lost_turn: Option<StatusKind>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Statuses(Vec<Status>);
+
+impl Statuses {
+    pub fn list(&self) -> &[Status] {
+        &self.0
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|status| status.kind == kind)
+    }
+
+    /// Reapplying a status keeps whichever duration and potency is stronger
+    pub fn apply(&mut self, status: Status) {
+        match self.0.iter_mut().find(|s| s.kind == status.kind) {
+            Some(existing) => {
+                existing.turns = existing.turns.max(status.turns);
+                existing.potency = existing.potency.max(status.potency);
+                existing.fresh = true;
+            }
+            None => self.0.push(status),
+        }
+    }
+
+    /// Removes poison, bleeding and burns, returns whether there were any
+    pub fn cure(&mut self) -> bool {
+        let before = self.0.len();
+        self.0.retain(|status| !status.kind.is_ailment());
+        before != self.0.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn damage_dealt_multiplier(&self) -> f64 {
+        if self.has(StatusKind::Weakened) {
+            WEAKENED_MULTIPLIER
+        } else {
+            1.
+        }
+    }
+
+    pub fn damage_taken_multiplier(&self) -> f64 {
+        if self.has(StatusKind::Shielded) {
+            SHIELDED_MULTIPLIER
+        } else {
+            1.
+        }
+    }
+
+    /// Called as the holder's turn starts. Applies damage and healing to `cur_health`,
+    /// pair with `end_turn`
+    pub fn start_turn(&mut self, cur_health: &mut u16, max_health: u16, rng: &mut GameRng) -> Tick {
+        let mut tick = Tick::default();
+        let burning = self.has(StatusKind::Burn);
+
+        for status in &mut self.0 {
+            status.fresh = false;
+            match status.kind {
+                StatusKind::Poison => {
+                    let damage = status.potency.min(cur_health.saturating_sub(1));
+                    *cur_health -= damage;
+                    tick.damage += damage;
+                }
+                StatusKind::Bleed | StatusKind::Burn => {
+                    let damage = status.potency.min(*cur_health);
+                    *cur_health -= damage;
+                    tick.damage += damage;
+                }
+                StatusKind::Regen if !burning => {
+                    let healed = status.potency.min(max_health - *cur_health);
+                    *cur_health += healed;
+                    tick.healed += healed;
+                }
+                StatusKind::Stun => tick.lost_turn = Some(StatusKind::Stun),
+                StatusKind::Chill
+                    if tick.lost_turn.is_none()
+                        && math::bool_from_chance(rng, CHILL_SKIP_CHANCE) =>
+                {
+                    tick.lost_turn = Some(StatusKind::Chill)
+                }
+                _ => {}
+            }
+        }
+        tick
+    }
+
+    /// Counts the turn against every status that was there when it started,
+    /// and drops whatever ran out
+    pub fn end_turn(&mut self) {
+        for status in self.0.iter_mut().filter(|status| !status.fresh) {
+            status.turns = status.turns.saturating_sub(1);
+        }
+        self.0.retain(|status| status.turns > 0);
+    }
+}
@@ -0,0 +1,4 @@
+//! Developer tools for tuning the game, run from the command line rather than played
+
+pub mod simulate;
+pub mod xp_curve;
@@ -0,0 +1,213 @@
+use crate::inform;
+use crate::show;
+
+use crate::classes::Class;
+use crate::content;
+use crate::entities::{Entity, attack_round};
+use crate::items::*;
+use crate::places::Place;
+use crate::player::Player;
+use crate::utilities::math::{self, GameRng};
+use crate::utilities::*;
+
+// A fight still going after this many rounds is a stalemate, counted as a loss
+const MAX_ROUNDS: u32 = 1_000;
+
+/// What the simulated player brings to every fight
+pub struct Loadout {
+    pub class: Option<Class>,
+    pub weapon: Option<Item>, // The class's starting weapon when unset
+    pub armor: Option<Item>,
+    pub level: u16,
+    pub max_health: Option<u16>, // Whatever `level` grows to when unset
+}
+
+// Whatever a brand new character starts with
+impl Default for Loadout {
+    fn default() -> Self {
+        let plr = Player::new(String::new());
+        Self {
+            class: plr.class,
+            weapon: None,
+            armor: plr.armor,
+            level: plr.level,
+            max_health: None,
+        }
+    }
+}
+
+pub fn class_from_arg(arg: &str) -> Result<Class, String> {
+    Class::get_registered()
+        .into_iter()
+        .find(|class| class.to_string().eq_ignore_ascii_case(arg))
+        .ok_or_else(|| format!("There's no class called `{arg}`"))
+}
+
+/// Either a flat damage number or the name of a weapon from the content directory
+pub fn weapon_from_arg(arg: &str) -> Result<Item, String> {
+    if let Ok(damage) = arg.parse::<u16>() {
+        return Ok(Item::new("Custom Weapon", "", ItemType::Weapon { damage }));
+    }
+    match content::get().item(arg) {
+        Some(itm) if matches!(itm.item_type, ItemType::Weapon { .. }) => Ok(itm.clone()),
+        Some(itm) => Err(format!(
+            "`{arg}` is a(n) {} item, not a weapon",
+            itm.item_type
+        )),
+        None => Err(format!("There's no item called `{arg}`")),
+    }
+}
+
+/// Either a reduction (0-1) or the name of an armor from the content directory
+pub fn armor_from_arg(arg: &str) -> Result<Item, String> {
+    if let Ok(reduction) = arg.parse::<f64>() {
+        if !(0. ..=1.).contains(&reduction) {
+            return Err(format!(
+                "An armor reduction of {reduction} isn't between 0 and 1"
+            ));
+        }
+        let resists = Resistances::default();
+        return Ok(Item::new(
+            "Custom Armor",
+            "",
+            ItemType::Armor { reduction, resists },
+        ));
+    }
+    match content::get().item(arg) {
+        Some(itm) if matches!(itm.item_type, ItemType::Armor { .. }) => Ok(itm.clone()),
+        Some(itm) => Err(format!("`{arg}` is a(n) {} item, not armor", itm.item_type)),
+        None => Err(format!("There's no item called `{arg}`")),
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    fights: u32,
+    wins: u32,
+    rounds: u64, // Up to `MAX_ROUNDS` a fight, so `u32` overflows over enough fights
+    hp_lost: u64,
+}
+
+impl Loadout {
+    fn player(&self, seed: u64) -> Player {
+        let name = String::from("Simulated");
+        let mut plr = match self.class {
+            Some(class) => Player::with_class(name, class),
+            None => Player::new(name),
+        };
+        if let Some(weapon) = &self.weapon {
+            plr.weapon = weapon.clone();
+        }
+        plr.armor = self.armor.clone();
+        plr.grow_to(self.level);
+        if let Some(max_health) = self.max_health {
+            plr.max_health = max_health;
+            plr.cur_health = max_health;
+        }
+        plr.rng = GameRng::seeded(seed);
+        plr
+    }
+
+    fn describe(&self) -> String {
+        let plr = self.player(0);
+        let class = self
+            .class
+            .map_or(String::from("No class"), |class| class.to_string());
+        let weapon = match plr.weapon.item_type {
+            ItemType::Weapon { damage } => format!("{} ({damage} damage)", plr.weapon.name),
+            _ => plr.weapon.name.clone(),
+        };
+        let armor = match &self.armor {
+            Some(Item {
+                name,
+                item_type: ItemType::Armor { reduction, .. },
+                ..
+            }) => format!("{name} ({:.0}% reduction)", reduction * 100.),
+            _ => String::from("no armor"),
+        };
+        format!(
+            "{class}, {weapon}, {armor}, level {}, {} HP, {} attack",
+            self.level,
+            plr.max_health,
+            plr.attack_damage()
+        )
+    }
+}
+
+/// Fights `entity` alone, though whoever it calls for help still joins in
+fn fight(entity: &Entity, plr: &mut Player) -> (bool, u32) {
+    let mut group = vec![entity.clone()];
+    plr.cur_health = plr.max_health;
+    plr.statuses.clear();
+
+    let mut rounds = 0;
+    while group.iter().any(Entity::in_fight) && plr.is_alive() && rounds < MAX_ROUNDS {
+        attack_round(&mut group, plr);
+        rounds += 1;
+    }
+    let beaten = group.iter().all(|entity| !entity.is_alive());
+    (beaten && plr.is_alive(), rounds)
+}
+
+/// Fights every entity, grown to the loadout's level, and boss of every place `fights` times
+/// and prints how the loadout fares
+pub fn simulate(loadout: &Loadout, fights: u32, seed: u64) {
+    let fights = fights.max(1);
+    inform!("Loadout: {}", loadout.describe());
+    inform!("{fights} fights per entity, seed {seed}\n");
+
+    let mut plr = loadout.player(seed);
+
+    for loc in Place::get_registered() {
+        let Some(place) = Place::new(loc) else {
+            continue;
+        };
+        plr.cur_place = place.clone(); // Where calls for help are answered from
+        show!("{}", paint_text(&place.name, "Green"));
+        show!(
+            "  {:<20}{:>8}{:>12}{:>14}{:>12}",
+            "Entity",
+            "Win %",
+            "Avg rounds",
+            "Avg HP lost",
+            "XP/fight"
+        );
+
+        let entities = place.get_entities().into_iter();
+        let entities = entities.map(|(entity, _)| entity.scaled(loadout.level));
+        for entity in entities.chain(place.get_boss()) {
+            let mut tally = Tally::default();
+            for _ in 0..fights {
+                let (won, rounds) = fight(&entity, &mut plr);
+                tally.fights += 1;
+                tally.wins += won as u32;
+                tally.rounds += rounds as u64;
+                tally.hp_lost += (plr.max_health - plr.cur_health) as u64;
+            }
+            report(&entity, &tally);
+        }
+        show!("");
+    }
+}
+
+fn report(entity: &Entity, tally: &Tally) {
+    let fights = tally.fights as f64;
+    let win_rate = tally.wins as f64 / fights;
+    // Only wins pay out, so this is what a fight is worth on average
+    let xp_per_fight = math::calc_xp_gain(entity) * win_rate;
+
+    let line = format!(
+        "  {:<20}{:>8.1}{:>12.1}{:>14.1}{:>12.1}",
+        entity.name,
+        win_rate * 100.,
+        tally.rounds as f64 / fights,
+        tally.hp_lost as f64 / fights,
+        xp_per_fight
+    );
+    let clr = match win_rate {
+        0.9.. => "Green",
+        0.5.. => "Cyan",
+        _ => "Red",
+    };
+    show!("{}", paint_text(line, clr));
+}
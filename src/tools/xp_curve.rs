@@ -0,0 +1,136 @@
+use crate::inform;
+use crate::show;
+
+use crate::places::Place;
+use crate::utilities::math::{LevelCurve, XpGain};
+use crate::utilities::*;
+
+/// The constants being compared against the game's own
+#[derive(Clone, Copy)]
+pub struct Tuning {
+    pub curve: LevelCurve,
+    pub gain: XpGain,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            curve: LevelCurve::DEFAULT,
+            gain: XpGain::DEFAULT,
+        }
+    }
+}
+
+// Levels shown in the kills table, on top of 1
+const KILL_STEP: u16 = 5;
+
+/// Prints XP needed per level up to `max_level` and how many kills of each entity
+/// a level takes, side by side with `alt` when given
+pub fn xp_curve(max_level: u16, alt: Option<Tuning>) {
+    let cur = Tuning::default();
+    let max_level = max_level.max(2);
+
+    describe("Current", &cur);
+    if let Some(alt) = &alt {
+        describe("Alternate", alt);
+    }
+
+    inform!("\n--- XP needed per level: ---\n");
+    show!(
+        "  {:<8}{:>12}{:>12}{}",
+        "Level",
+        "Total",
+        "This level",
+        if alt.is_some() { "   (alternate)" } else { "" }
+    );
+    for lvl in 2..=max_level {
+        let mut line = format!(
+            "  {:<8}{:>12}{:>12}",
+            lvl,
+            total_xp(&cur.curve, lvl),
+            level_cost(&cur.curve, lvl - 1)
+        );
+        if let Some(alt) = &alt {
+            line += &paint_text(
+                format!(
+                    "   {} / {}",
+                    total_xp(&alt.curve, lvl),
+                    level_cost(&alt.curve, lvl - 1)
+                ),
+                "Cyan",
+            );
+        }
+        show!("{line}");
+    }
+
+    let levels: Vec<u16> = std::iter::once(1)
+        .chain((KILL_STEP..max_level).step_by(KILL_STEP as usize))
+        .collect();
+
+    inform!("\n--- Kills to reach the next level (XP/kill at level 1): ---");
+    for loc in Place::get_registered() {
+        let Some(place) = Place::new(loc) else {
+            continue;
+        };
+        show!("\n{}", paint_text(&place.name, "Green"));
+
+        let header: String = levels
+            .iter()
+            .map(|lvl| format!("{:>14}", format!("Lv {lvl}->{}", lvl + 1)))
+            .collect();
+        show!("  {:<20}{:>12}{header}", "Entity", "XP/kill");
+
+        for (entity, _) in place.get_entities() {
+            let cur_xp = cur.gain.for_entity(&entity);
+            let alt_xp = alt.map(|alt| alt.gain.for_entity(&entity));
+
+            let mut line = format!("  {:<20}{:>12}", entity.name, compare(cur_xp, alt_xp));
+            for &lvl in &levels {
+                // Entities grow with the player, and pay out more for it
+                let scaled = entity.clone().scaled(lvl);
+                let cur_xp = cur.gain.for_entity(&scaled);
+                let alt_xp = alt.map(|alt| alt.gain.for_entity(&scaled));
+
+                let cur_kills = kills(level_cost(&cur.curve, lvl), cur_xp);
+                let alt_kills =
+                    alt.map(|alt| kills(level_cost(&alt.curve, lvl), alt_xp.unwrap_or(0.)));
+                line += &format!("{:>14}", compare(cur_kills, alt_kills));
+            }
+            show!("{line}");
+        }
+    }
+}
+
+fn describe(label: &str, tuning: &Tuning) {
+    let Tuning {
+        curve: LevelCurve { a, b, c },
+        gain: XpGain { omega, rho, xi },
+    } = tuning;
+    inform!("{label} curve: level = {a} * ln(xp + {b}) + {c}");
+    inform!("{label} gain:  xp = {xi} * (max_hp * (dmg_avg + {omega} * dmg_delta)) ^ {rho}");
+}
+
+/// XP between reaching `lvl` and reaching the one after it
+// Curves can dip below 0 XP at low levels, the player starts at 0 either way
+fn total_xp(curve: &LevelCurve, lvl: u16) -> f64 {
+    curve.xp_needed(lvl).max(0.)
+}
+
+fn level_cost(curve: &LevelCurve, lvl: u16) -> f64 {
+    (curve.xp_needed(lvl + 1) - total_xp(curve, lvl)).max(0.)
+}
+
+fn kills(cost: f64, xp_per_kill: f64) -> f64 {
+    if xp_per_kill <= 0. {
+        return f64::INFINITY;
+    }
+    (cost / xp_per_kill).ceil()
+}
+
+// e.g., "12" or "12 -> 9" against the alternate
+fn compare(cur: f64, alt: Option<f64>) -> String {
+    match alt {
+        Some(alt) if alt != cur => format!("{cur} -> {alt}"),
+        _ => cur.to_string(),
+    }
+}
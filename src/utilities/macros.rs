@@ -1,22 +1,28 @@
 #[macro_export]
 macro_rules! warn {
-    ($($arg:expr),*) => {
-        println!("{}{}{}{}{}", color("Red"), style("Italics"), format!($($arg),*), color("Reset"), style("Reset"))
+    ($($arg:expr),* $(,)?) => {
+        $crate::frontend::show_message(&format!($($arg),*), $crate::frontend::Tone::Warning)
     };
 }
 
 #[macro_export]
 macro_rules! inform {
-    ($($arg:expr),*) => {
-        println!("{}{}{}", color("Blue"), format!($($arg),*), color("Reset"))
+    ($($arg:expr),* $(,)?) => {
+        $crate::frontend::show_message(&format!($($arg),*), $crate::frontend::Tone::Info)
+    };
+}
+
+/// `println!`, but through the current frontend
+#[macro_export]
+macro_rules! show {
+    ($($arg:expr),* $(,)?) => {
+        $crate::frontend::show_message(&format!($($arg),*), $crate::frontend::Tone::Plain)
     };
 }
 
 #[macro_export]
 macro_rules! prompt {
-    ($($arg:expr),*) => {
-        input!("\n{}{}{}", color("Blue"), format!($($arg),*), color("Reset"))
-            .trim()
-            .to_string()
+    ($($arg:expr),* $(,)?) => {
+        $crate::frontend::ask_text(&format!($($arg),*))
     };
 }
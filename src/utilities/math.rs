@@ -1,61 +1,251 @@
+use std::fmt::Display;
+
 use rand::distr::{Distribution, uniform::SampleUniform, weighted::WeightedIndex};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::entities::Entity;
 
 // ------------ RNG Math: --------------
-pub fn rng_from_range<T>(range_tuple: (T, T)) -> T
+
+/// The game's one source of randomness, so the same seed reproduces the same run.
+/// A pinned algorithm, unlike `StdRng`, so seeds hold across rand versions and platforms
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+// A random seed, still kept around so the run can be reproduced
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::seeded(rand::random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst)
+    }
+}
+
+/// Takes the bounds in either order, content with min above max still rolls
+pub fn rng_from_range<T>(rng: &mut impl Rng, range_tuple: (T, T)) -> T
 where
     T: SampleUniform + PartialOrd + Copy,
 {
-    rand::random_range(range_tuple.0..=range_tuple.1)
+    let (min, max) = range_tuple;
+    if min > max {
+        rng.random_range(max..=min)
+    } else {
+        rng.random_range(min..=max)
+    }
+}
+
+/// Chances from content aren't checked when the game loads them (only by `check-content`),
+/// so anything past 0 or 1 counts as never or always rather than panicking mid-fight
+pub fn bool_from_chance(rng: &mut impl Rng, chance: f64) -> bool {
+    if chance.is_nan() {
+        return false;
+    }
+    rng.random_bool(chance.clamp(0., 1.))
+}
+
+// ------------ Combat Math: -----------
+
+const CRIT_MULTIPLIER: f64 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HitOutcome {
+    Miss,
+    Dodge,
+    Hit,
+    Crit,
+}
+
+impl Display for HitOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Miss => write!(f, "MISS"),
+            Self::Dodge => write!(f, "DODGED"),
+            Self::Hit => write!(f, "HIT"),
+            Self::Crit => write!(f, "CRIT"),
+        }
+    }
+}
+
+impl HitOutcome {
+    /// Of the attack's damage
+    pub fn multiplier(&self) -> f64 {
+        match self {
+            Self::Miss | Self::Dodge => 0.,
+            Self::Hit => 1.,
+            Self::Crit => CRIT_MULTIPLIER,
+        }
+    }
+
+    pub fn landed(&self) -> bool {
+        matches!(self, Self::Hit | Self::Crit)
+    }
 }
 
-pub fn bool_from_chance(chance: f64) -> bool {
-    rand::random_bool(chance)
+/// The attacker has to hit, then get past the defender's dodge, then may crit
+pub fn roll_hit(
+    rng: &mut impl Rng,
+    hit_chance: f64,
+    dodge_chance: f64,
+    crit_chance: f64,
+) -> HitOutcome {
+    if !bool_from_chance(rng, hit_chance) {
+        HitOutcome::Miss
+    } else if bool_from_chance(rng, dodge_chance) {
+        HitOutcome::Dodge
+    } else if bool_from_chance(rng, crit_chance) {
+        HitOutcome::Crit
+    } else {
+        HitOutcome::Hit
+    }
 }
 
-pub fn weigh_vec<T>(vec: Vec<(T, f64)>) -> Option<T> {
+pub fn weigh_vec<T>(rng: &mut impl Rng, vec: Vec<(T, f64)>) -> Option<T> {
     if vec.is_empty() {
         return None;
     }
 
     let (mut items, weights): (Vec<_>, Vec<_>) = vec.into_iter().unzip();
 
-    let mut rng = rand::rng();
-    let index_yield = WeightedIndex::new(weights).ok()?.sample(&mut rng);
+    let index_yield = WeightedIndex::new(weights).ok()?.sample(rng);
 
     Some(items.swap_remove(index_yield))
 }
 
 // ------------ Leveling Math:-----------
 
-const A: f64 = 12.677;
-const B: f64 = 1218.390;
-const C: f64 = -89.075;
+/// level = a * ln(xp + b) + c
+#[derive(Clone, Copy)]
+pub struct LevelCurve {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl LevelCurve {
+    pub const DEFAULT: Self = Self {
+        a: 12.677,
+        b: 1218.390,
+        c: -89.075,
+    };
+
+    pub fn level(&self, xp: f64) -> u16 {
+        (((self.a * (xp + self.b).ln() + self.c).floor()) as u16).clamp(1, u16::MAX)
+    }
+
+    pub fn xp_needed(&self, lvl: u16) -> f64 {
+        (((lvl as f64 - self.c) / self.a).exp() - self.b).ceil()
+    }
+}
+
+/// xp = xi * (max_hp * (dmg_avg + omega * dmg_delta)) ^ rho
+#[derive(Clone, Copy)]
+pub struct XpGain {
+    pub omega: f64, // How much a wide damage range counts
+    pub rho: f64,   // < 1 so tougher entities pay out less per point of score
+    pub xi: f64,
+}
+
+impl XpGain {
+    pub const DEFAULT: Self = Self {
+        omega: 0.3,
+        rho: 0.80,
+        xi: 0.075,
+    };
+
+    pub fn for_entity(&self, entity: &Entity) -> f64 {
+        let Entity {
+            max_health,
+            damage,
+            rank,
+            ..
+        } = *entity;
+        let (max_hp, min_dmg, max_dmg) = (max_health as f64, damage.0 as f64, damage.1 as f64);
+
+        let dmg_avg = (min_dmg + max_dmg) / 2.;
+        let dmg_delta = (max_dmg - min_dmg) / 2.;
+
+        let score = max_hp * (dmg_avg + self.omega * dmg_delta);
+        (self.xi * score.powf(self.rho) * rank.xp_multiplier()).ceil()
+    }
+}
 
 pub fn calc_level(xp: f64) -> u16 {
-    (((A * (xp + B).ln() + C).floor()) as u16).clamp(1, u16::MAX)
+    LevelCurve::DEFAULT.level(xp)
 }
 
 pub fn xp_needed(lvl: u16) -> f64 {
-    (((lvl as f64 - C) / A).exp() - B).ceil()
+    LevelCurve::DEFAULT.xp_needed(lvl)
 }
 
 pub fn calc_xp_gain(entity: &Entity) -> f64 {
-    let Entity {
-        max_health, damage, ..
-    } = *entity;
-    let (max_hp, min_dmg, max_dmg) = (max_health as f64, damage.0 as f64, damage.1 as f64);
+    XpGain::DEFAULT.for_entity(entity)
+}
+
+// ----------------------------
 
-    let dmg_avg = (min_dmg + max_dmg) / 2.;
-    let dmg_delta = (max_dmg - min_dmg) / 2.;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolls(seed: u64) -> (Vec<u16>, Vec<&'static str>) {
+        let mut rng = GameRng::seeded(seed);
+        let ranged = (0..20)
+            .map(|_| rng_from_range(&mut rng, (1, 100)))
+            .collect();
+        let weighted = (0..20)
+            .map(|_| weigh_vec(&mut rng, vec![("a", 0.5), ("b", 0.3), ("c", 0.2)]).unwrap())
+            .collect();
+        (ranged, weighted)
+    }
 
-    const OMEGA: f64 = 0.3;
-    const RHO: f64 = 0.80;
-    const XI: f64 = 0.075;
+    #[test]
+    fn same_seed_same_rolls() {
+        assert_eq!(rolls(42), rolls(42));
+        assert_ne!(rolls(42), rolls(43));
+    }
 
-    let score = max_hp * (dmg_avg + OMEGA * dmg_delta);
-    (XI * score.powf(RHO)).ceil()
-}
+    // If this changes, seeds from older bug reports no longer reproduce their runs
+    #[test]
+    fn seeds_reproduce_known_rolls() {
+        let mut rng = GameRng::seeded(42);
+        let ranged: Vec<u16> = (0..5).map(|_| rng_from_range(&mut rng, (1, 100))).collect();
+        assert_eq!(ranged, vec![23, 69, 15, 96, 78]);
+    }
 
-// ----------------------------
+    #[test]
+    fn content_out_of_bounds_still_rolls() {
+        let mut rng = GameRng::seeded(42);
+        assert!(bool_from_chance(&mut rng, 1.5));
+        assert!(!bool_from_chance(&mut rng, -0.5));
+        assert!(!bool_from_chance(&mut rng, f64::NAN));
+        assert!((5..=10).contains(&rng_from_range(&mut rng, (10, 5))));
+    }
+}
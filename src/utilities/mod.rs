@@ -1,14 +1,15 @@
-use std::fs::File;
-use std::{fmt::Display, io::Read};
+use std::fmt::Display;
 use termion::*;
 
+use crate::frontend;
+use crate::show;
 use crate::warn;
 
 pub mod macros;
 pub mod math;
 
 pub fn clear_terminal() {
-    print!("{}[2J", 27 as char);
+    frontend::clear();
 }
 
 /// I love you indexize <3
@@ -35,19 +36,14 @@ where
 {
     for (i, opt) in opts.into_iter().enumerate() {
         let list_num = i + 1;
-        println!(
-            "{}[{list_num}] >> {}{}{}",
-            color("Cyan"),
-            color("LightMagenta"),
-            opt,
-            color("Reset")
-        );
+        show!("{}", frontend::list_entry(list_num, &opt.to_string()));
     }
 }
 
+/// Fine with any `max`, content can give an entity less health than there are segments
 pub fn progress_bar(current: u16, max: u16, clr: &str, segs: u16) -> String {
-    let to_eq = max / segs;
-    let curr_eq = (current / to_eq).min(segs) as usize;
+    let filled = (current as u32 * segs as u32).checked_div(max as u32);
+    let curr_eq = filled.unwrap_or_default().min(segs as u32) as usize;
     let remaining = (segs as usize) - curr_eq;
 
     format!(
@@ -71,6 +67,7 @@ pub fn color(s: &str) -> Box<dyn std::fmt::Display> {
         "Magenta" => Box::new(color::Fg(color::Magenta)),
         "Red" => Box::new(color::Fg(color::Red)),
         "Reset" => Box::new(color::Fg(color::Reset)),
+        "Yellow" => Box::new(color::Fg(color::Yellow)),
         _ => panic!("Color `{}` is not registered", s),
     }
 }
@@ -89,23 +86,30 @@ pub fn style(s: &str) -> &'static dyn std::fmt::Display {
     }
 }
 
+pub const SPRITE_DIR: &str = "src/sprites";
+
 pub fn show_sprite(path: String) {
-    let operation = File::open(
-        format!("src/sprites/{}", path), // e.g., places/forest.ans
-    );
+    frontend::show_sprite(&path);
+}
 
-    let mut content = match operation {
-        Ok(file) => file,
-        Err(er) => return eprintln!("Loading sprite failed: `{er}`"),
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut buffer = String::new();
-    let loader = content.read_to_string(&mut buffer);
+    fn filled(bar: &str) -> usize {
+        bar.matches('=').count()
+    }
 
-    match loader {
-        Ok(_) => (),
-        Err(er) => return eprintln!("Loading sprite failed: `{er}`"),
-    };
+    #[test]
+    fn progress_bar_handles_tiny_and_empty_maxes() {
+        assert_eq!(filled(&progress_bar(3, 5, "Green", 10)), 6);
+        assert_eq!(filled(&progress_bar(1, 1, "Green", 10)), 10);
+        assert_eq!(filled(&progress_bar(0, 0, "Green", 10)), 0);
+    }
 
-    println!("{buffer}");
+    #[test]
+    fn progress_bar_never_overflows() {
+        assert_eq!(filled(&progress_bar(120, 75, "Green", 10)), 10);
+        assert_eq!(filled(&progress_bar(37, 75, "Green", 10)), 4);
+    }
 }